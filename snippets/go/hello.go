@@ -0,0 +1,11 @@
+package main
+
+import "fmt"
+
+func main() {
+	names := []string{"Ada", "Grace", "Linus"}
+
+	for i, name := range names {
+		fmt.Printf("%d: hello, %s\n", i, name)
+	}
+}
@@ -0,0 +1,15 @@
+// Max returns the largest value of the slice
+func Max(values []int) (int, error) {
+	if len(values) == 0 {
+		return 0, errors.New("empty slice")
+	}
+
+	max := values[0]
+	for _, value := range values[1:] {
+		if value > max {
+			max = value
+		}
+	}
+
+	return max, nil
+}
@@ -0,0 +1,6 @@
+import sys
+
+# Prints the numbered lines of a file
+with open(sys.argv[1], encoding="utf-8") as file:
+    for number, line in enumerate(file, start=1):
+        print(f"{number:4} {line.rstrip()}")
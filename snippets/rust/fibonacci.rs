@@ -0,0 +1,10 @@
+/// Returns the n-th Fibonacci number
+fn fibonacci(n: u64) -> u64 {
+    let (mut a, mut b) = (0, 1);
+
+    for _ in 0..n {
+        (a, b) = (b, a + b);
+    }
+
+    a
+}
@@ -1,6 +1,7 @@
-use crate::config::TukaiConfig;
+use crate::config::{TukaiConfig, TukaiConfigBuilder, TypingMode};
 use crate::event_handler::{EventHandler, TukaiEvent};
 use crate::screens::ActiveScreenEnum;
+use crate::screens::duration_prompt::{DurationPrompt, DurationPromptEvent};
 use crate::screens::repeat::RepeatScreen;
 use crate::screens::practice::PracticeScreen;
 use crate::screens::{Screen, stats::StatsScreen, typing::TypingScreen};
@@ -34,32 +35,32 @@ pub struct Tukai<'a> {
 
   // Displayed screen (typing|stats)
   screen: Box<dyn Screen>,
+
+  // Prompt of the custom typing duration (visible if set)
+  duration_prompt: Option<DurationPrompt>,
 }
 
 impl<'a> Tukai<'a> {
   /// Attempts to create a new Tukai application.
   /// Tries to initialize `StorageHandler` then load
   /// an existing saved settings file.
-  pub fn try_new(event_handler: &'a mut EventHandler, mut config: TukaiConfig) -> Result<Self> {
-    let storage_handler = StorageHandler::new(config.get_file_path()).init()?;
-
-    config.typing_duration = storage_handler.get_typing_duration();
-    config.has_transparent_bg = storage_handler.get_has_transparent_bg();
+  ///
+  /// Values set explicitly in the `config_builder`
+  /// take precedence over the saved settings.
+  pub fn try_new(
+    event_handler: &'a mut EventHandler,
+    config_builder: TukaiConfigBuilder,
+  ) -> Result<Self> {
+    let storage_handler = StorageHandler::new(config_builder.get_file_path()).init()?;
 
-    {
-      let mut layout = config.get_layout_mut();
-      layout.active_layout_name(storage_handler.get_layout_name().clone());
-    }
-
-    {
-      let mut language = config.get_language_mut();
-      language.current_index(storage_handler.get_language_index());
-    }
+    let config = config_builder.storage_fallback(&storage_handler).build();
+    let screen = config.get_screen();
 
     let config = Rc::new(RefCell::new(config));
-    let typing_screen = TypingScreen::new(Rc::clone(&config));
 
     Ok(Self {
+      screen: Tukai::create_screen(screen, Rc::clone(&config), &storage_handler),
+
       config,
 
       event_handler,
@@ -68,26 +69,47 @@ impl<'a> Tukai<'a> {
 
       is_terminated: false,
 
-      screen: Box::new(typing_screen),
+      duration_prompt: None,
     })
   }
 
+  /// Creates a screen of the given type
+  ///
+  /// The stored runs are read from the storage handler of the app.
+  fn create_screen(
+    screen: ActiveScreenEnum,
+    config: Rc<RefCell<TukaiConfig>>,
+    storage_handler: &StorageHandler,
+  ) -> Box<dyn Screen> {
+    match screen {
+      ActiveScreenEnum::Typing => Box::new(TypingScreen::new(config, storage_handler)),
+      ActiveScreenEnum::Repeat => Box::new(RepeatScreen::new(config)),
+      ActiveScreenEnum::Practice => Box::new(PracticeScreen::new(config, storage_handler)),
+      ActiveScreenEnum::Stats => Box::new(StatsScreen::new(config)),
+    }
+  }
+
   /// Runs and renders tui components.
   ///
   /// Handles events from `EventHandler`
-  /// Handles tick (seconds, it's time counter) from `EventHandler`
+  /// Handles tick (redraws the run clock and live stats) from `EventHandler`
   pub async fn run(&mut self, terminal: &mut TukaiTerminal) -> Result<()> {
     while !self.is_terminated {
       match self.event_handler.next().await? {
         TukaiEvent::Key(key_event) => self.handle_events(key_event),
-        TukaiEvent::Tick => {
-          if self.screen.is_running() {
-            self.screen.increment_time_secs();
+        // Screens measure the time themselves, the tick only checks the end and redraws
+        TukaiEvent::Tick => {}
+        TukaiEvent::Frame => {
+          // Redraws only the animated screens
+          if !self.screen.is_animated() {
+            continue;
           }
+
+          self.screen.animate();
         }
       };
 
-      if self.screen.get_remaining_time() == 0 {
+      if self.screen.is_finished() {
         self.screen.stop(&mut self.storage_handler);
       }
 
@@ -110,11 +132,15 @@ impl<'a> Tukai<'a> {
     if self.screen.is_popup_visible() {
       self.screen.render_popup(frame);
     }
+
+    if let Some(duration_prompt) = &self.duration_prompt {
+      duration_prompt.render(frame, &self.config.borrow());
+    }
   }
 
   /// Resets the application
   fn reset(&mut self) {
-    self.screen.reset();
+    self.screen.reset(&self.storage_handler);
   }
 
   /// Exits the running application
@@ -134,20 +160,53 @@ impl<'a> Tukai<'a> {
   /// Hides the currently active screen.
   /// Sets the `active_screen` to the switched screen
   fn switch_screen(&mut self, switch_to_screen: ActiveScreenEnum) {
-    self.screen = match switch_to_screen {
-      ActiveScreenEnum::Typing => Box::new(TypingScreen::new(self.config.clone())),
-      ActiveScreenEnum::Repeat => Box::new(RepeatScreen::new(self.config.clone())),
-      ActiveScreenEnum::Practice => Box::new(PracticeScreen::new(self.config.clone())),
-      ActiveScreenEnum::Stats => Box::new(StatsScreen::new(self.config.clone())),
+    self.screen =
+      Tukai::create_screen(switch_to_screen, self.config.clone(), &self.storage_handler);
+  }
+
+  /// Handles events of the opened custom duration prompt.
+  ///
+  /// The confirmed duration selects a timed run.
+  fn handle_duration_prompt_events(&mut self, key_event: KeyEvent) {
+    let Some(duration_prompt) = self.duration_prompt.as_mut() else {
+      return;
+    };
+
+    match duration_prompt.handle_events(key_event) {
+      DurationPromptEvent::Consumed => {}
+      DurationPromptEvent::Cancelled => self.duration_prompt = None,
+      DurationPromptEvent::Confirmed(typing_duration) => {
+        self.duration_prompt = None;
+
+        {
+          let mut config = self.config.borrow_mut();
+          config.typing_duration = typing_duration.clone();
+          config.typing_mode = TypingMode::Time;
+        }
+
+        self.storage_handler.set_typing_duration(typing_duration);
+        self.storage_handler.set_typing_mode(TypingMode::Time);
+
+        self.reset();
+      }
     }
   }
 
   /// Handles crossterm events.
   ///
+  /// The opened duration prompt consumes all keys except ctrl-c.
   /// First, checks for events with the pressed control button.
   /// Then, handles `screen` events (TypingScreen).
   /// Finally, processes remainig keys.
   fn handle_events(&mut self, key_event: KeyEvent) {
+    if self.duration_prompt.is_some()
+      && !(key_event.modifiers.contains(KeyModifiers::CONTROL)
+        && key_event.code == KeyCode::Char('c'))
+    {
+      self.handle_duration_prompt_events(key_event);
+      return;
+    }
+
     if key_event.modifiers.contains(KeyModifiers::CONTROL) {
       // Handle screen specific CTRL key events
       if self.screen.handle_control_events(key_event) {
@@ -169,9 +228,28 @@ impl<'a> Tukai<'a> {
           }
           'c' => self.exit(),
           'd' => {
+            // Word count runs switch the count of words instead
+            let typing_mode = self.config.borrow().typing_mode;
+
+            if let TypingMode::Words(_) = typing_mode {
+              self
+                .storage_handler
+                .set_typing_mode(self.config.borrow_mut().switch_words_count());
+            } else {
+              self
+                .storage_handler
+                .set_typing_duration(self.config.borrow_mut().switch_typing_duration());
+            }
+
+            self.reset();
+          }
+          'u' if !self.screen.is_running() => {
+            self.duration_prompt = Some(DurationPrompt::new());
+          }
+          'n' => {
             self
               .storage_handler
-              .set_typing_duration(self.config.borrow_mut().switch_typing_duration());
+              .set_typing_mode(self.config.borrow_mut().switch_typing_mode());
 
             self.reset();
           }
@@ -179,6 +257,30 @@ impl<'a> Tukai<'a> {
             let new_state = self.config.borrow_mut().toggle_transparent_bg();
             self.storage_handler.set_transparent_bg(new_state);
           }
+          'a' => {
+            let new_state = self.config.borrow_mut().toggle_auto_indent();
+            self.storage_handler.set_auto_indent(new_state);
+          }
+          'f' => {
+            let new_state = self.config.borrow_mut().toggle_punctuation();
+            self.storage_handler.set_punctuation(new_state);
+            self.reset();
+          }
+          'k' => {
+            let new_state = self.config.borrow_mut().toggle_capitalization();
+            self.storage_handler.set_capitalization(new_state);
+            self.reset();
+          }
+          'y' => {
+            let new_state = self.config.borrow_mut().toggle_numbers();
+            self.storage_handler.set_numbers(new_state);
+            self.reset();
+          }
+          'g' => {
+            let new_state = self.config.borrow_mut().toggle_ghost();
+            self.storage_handler.set_ghost(new_state);
+            self.reset();
+          }
           's' => {
             let new_layout = self
               .config
@@ -190,13 +292,13 @@ impl<'a> Tukai<'a> {
           }
           'p' => {
             // switches language
-            let new_language_index = self
+            let new_lang_code = self
               .config
               .borrow_mut()
               .get_language_mut()
               .switch_language();
 
-            self.storage_handler.set_language_index(new_language_index);
+            self.storage_handler.set_lang_code(new_lang_code);
             self.reset();
           }
           _ => {}
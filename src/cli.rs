@@ -0,0 +1,453 @@
+use std::{
+  io::Write,
+  path::{Path, PathBuf},
+};
+
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+
+use crate::config::{
+  Language, TukaiConfigBuilder, TukaiLayoutName, TypingDuration, TypingMode, WORD_COUNTS,
+};
+use crate::event_handler::DEFAULT_TICK_RATE_MS;
+use crate::screens::ActiveScreenEnum;
+use crate::storage::{
+  stat_export::{ExportFormat, StatExport},
+  stat_import::{ImportFormat, StatImport},
+  storage_handler::StorageHandler,
+};
+
+/// Terminal-based touch typing application
+///
+/// Options which are not set fall back to the values saved in the storage file.
+/// Options apply only to the launched session, settings switched
+/// in the application are saved as usual.
+#[derive(Parser, Debug)]
+#[command(version, about)]
+pub struct TukaiCli {
+  /// Screen displayed after the start
+  #[arg(short, long, value_enum, default_value_t = ActiveScreenEnum::Typing)]
+  pub screen: ActiveScreenEnum,
+
+  /// Typing duration in seconds (i.e. 15, 30, 60, 180 or any custom count)
+  #[arg(short, long, value_name = "SECONDS", value_parser = parse_typing_duration)]
+  pub duration: Option<TypingDuration>,
+
+  /// Type the count of words instead of a timed run (10, 25, 50 or 100)
+  #[arg(short, long, value_name = "COUNT", value_parser = parse_words_count, conflicts_with_all = ["duration", "zen", "quote", "code"])]
+  pub words: Option<usize>,
+
+  /// Type until finished with enter, words are appended endlessly
+  #[arg(short, long, conflicts_with_all = ["duration", "quote", "code"])]
+  pub zen: bool,
+
+  /// Type a quote of the language, finishes when the quote is typed
+  #[arg(short, long, conflicts_with_all = ["duration", "code"])]
+  pub quote: bool,
+
+  /// Type a code snippet (of the programming language, e.g. rust, python, go)
+  #[arg(short, long, value_name = "LANG", num_args = 0..=1, default_missing_value = "", conflicts_with = "duration")]
+  pub code: Option<String>,
+
+  /// Skip the leading indentation of the code lines
+  #[arg(long, conflicts_with = "no_auto_indent")]
+  pub auto_indent: bool,
+
+  /// Type the leading indentation of the code lines
+  #[arg(long)]
+  pub no_auto_indent: bool,
+
+  /// Language code of the dictionary (e.g. en, fr, pl)
+  #[arg(short, long, value_name = "CODE", value_parser = parse_lang_code)]
+  pub language: Option<String>,
+
+  /// Color layout
+  #[arg(long, value_enum)]
+  pub layout: Option<TukaiLayoutName>,
+
+  /// Use a transparent background
+  #[arg(short, long, conflicts_with = "no_transparent")]
+  pub transparent: bool,
+
+  /// Use the layout background color
+  #[arg(long)]
+  pub no_transparent: bool,
+
+  /// Race against the ghost cursor of the personal best
+  #[arg(short, long, conflicts_with = "no_ghost")]
+  pub ghost: bool,
+
+  /// Hide the ghost cursor of the personal best
+  #[arg(long)]
+  pub no_ghost: bool,
+
+  /// Add punctuation into the generated text
+  #[arg(long, conflicts_with = "no_punctuation")]
+  pub punctuation: bool,
+
+  /// Generate the text without punctuation
+  #[arg(long)]
+  pub no_punctuation: bool,
+
+  /// Start the generated sentences with a capital letter
+  #[arg(long, conflicts_with = "no_capitalization")]
+  pub capitalization: bool,
+
+  /// Generate the lowercase text
+  #[arg(long)]
+  pub no_capitalization: bool,
+
+  /// Add numbers into the generated text
+  #[arg(long, conflicts_with = "no_numbers")]
+  pub numbers: bool,
+
+  /// Generate the text without numbers
+  #[arg(long)]
+  pub no_numbers: bool,
+
+  /// Interval of the screen refresh in milliseconds (the run clock and live stats)
+  #[arg(long, value_name = "MS", default_value_t = DEFAULT_TICK_RATE_MS, value_parser = clap::value_parser!(u64).range(10..=1000))]
+  pub tick_rate: u64,
+
+  /// Path to the storage file
+  ///
+  /// Relative paths are resolved against the current directory.
+  #[arg(long, value_name = "PATH", global = true)]
+  pub storage_path: Option<PathBuf>,
+
+  /// Command executed instead of starting the application
+  #[command(subcommand)]
+  pub command: Option<TukaiCommand>,
+}
+
+/// Commands working with the storage file
+#[derive(Subcommand, Debug)]
+pub enum TukaiCommand {
+  /// Exports the typing history
+  Export {
+    /// Output format [default: output file extension or csv]
+    #[arg(short, long, value_enum)]
+    format: Option<ExportFormat>,
+
+    /// Output file [default: standard output]
+    #[arg(short, long, value_name = "PATH")]
+    output: Option<PathBuf>,
+  },
+
+  /// Imports runs from an exported file or another storage file
+  ///
+  /// Runs already present in the storage are skipped.
+  Import {
+    /// Input format [default: input file extension, other files are read as a storage file]
+    #[arg(short, long, value_enum)]
+    format: Option<ImportFormat>,
+
+    /// Input file
+    #[arg(value_name = "PATH")]
+    input: PathBuf,
+  },
+}
+
+impl TukaiCommand {
+  /// Executes the command on the storage file
+  pub fn execute<P: AsRef<Path>>(self, storage_file_path: P) -> Result<()> {
+    let mut storage_handler = StorageHandler::new(storage_file_path).init()?;
+
+    match self {
+      TukaiCommand::Export { format, output } => {
+        let format = format
+          .or_else(|| output.as_ref().and_then(ExportFormat::from_path))
+          .unwrap_or(ExportFormat::Csv);
+
+        let stats = storage_handler.get_data_stats();
+
+        match output {
+          Some(output) => StatExport::write_into_file(stats, format, output)?,
+          None => {
+            let mut stdout = std::io::stdout().lock();
+            StatExport::write(stats, format, &mut stdout)?;
+            stdout.flush()?;
+          }
+        }
+      }
+      TukaiCommand::Import { format, input } => {
+        let format = format.unwrap_or_else(|| ImportFormat::from_path(&input));
+
+        let stats = StatImport::read_from_file(format, &input)?;
+        let stats_count = stats.len();
+
+        let inserted_count = storage_handler.merge_stats(stats)?;
+
+        println!(
+          "Imported {inserted_count} runs, skipped {} already stored runs",
+          stats_count - inserted_count
+        );
+      }
+    }
+
+    Ok(())
+  }
+}
+
+impl TukaiCli {
+  /// Returns the background transparency, if it was set explicitly
+  fn get_has_transparent_bg(&self) -> Option<bool> {
+    get_explicit_flag(self.transparent, self.no_transparent)
+  }
+
+  /// Returns the ghost cursor visibility, if it was set explicitly
+  fn get_has_ghost(&self) -> Option<bool> {
+    get_explicit_flag(self.ghost, self.no_ghost)
+  }
+
+  /// Returns the skipping of the code indentation, if it was set explicitly
+  fn get_has_auto_indent(&self) -> Option<bool> {
+    get_explicit_flag(self.auto_indent, self.no_auto_indent)
+  }
+
+  /// Returns the punctuation of the generated text, if it was set explicitly
+  fn get_has_punctuation(&self) -> Option<bool> {
+    get_explicit_flag(self.punctuation, self.no_punctuation)
+  }
+
+  /// Returns the capitalization of the generated text, if it was set explicitly
+  fn get_has_capitalization(&self) -> Option<bool> {
+    get_explicit_flag(self.capitalization, self.no_capitalization)
+  }
+
+  /// Returns the numbers of the generated text, if it was set explicitly
+  fn get_has_numbers(&self) -> Option<bool> {
+    get_explicit_flag(self.numbers, self.no_numbers)
+  }
+
+  /// Returns the type of the test, if it was set explicitly
+  ///
+  /// Setting the duration selects the timed run.
+  fn get_typing_mode(&self) -> Option<TypingMode> {
+    if self.zen {
+      Some(TypingMode::Zen)
+    } else if self.quote {
+      Some(TypingMode::Quote)
+    } else if self.code.is_some() {
+      Some(TypingMode::Code)
+    } else if let Some(words_count) = self.words {
+      Some(TypingMode::Words(words_count))
+    } else {
+      self.duration.as_ref().map(|_| TypingMode::Time)
+    }
+  }
+
+  /// Creates a `TukaiConfigBuilder` from the parsed arguments.
+  pub fn into_config_builder(self) -> TukaiConfigBuilder {
+    let mut builder = TukaiConfigBuilder::new().screen(self.screen);
+
+    if let Some(storage_path) = &self.storage_path {
+      builder =
+        builder.file_path(std::path::absolute(storage_path).unwrap_or(storage_path.clone()));
+    }
+
+    if let Some(has_transparent_bg) = self.get_has_transparent_bg() {
+      builder = builder.has_transparent_bg(has_transparent_bg);
+    }
+
+    if let Some(has_ghost) = self.get_has_ghost() {
+      builder = builder.has_ghost(has_ghost);
+    }
+
+    if let Some(has_auto_indent) = self.get_has_auto_indent() {
+      builder = builder.has_auto_indent(has_auto_indent);
+    }
+
+    if let Some(has_punctuation) = self.get_has_punctuation() {
+      builder = builder.has_punctuation(has_punctuation);
+    }
+
+    if let Some(has_capitalization) = self.get_has_capitalization() {
+      builder = builder.has_capitalization(has_capitalization);
+    }
+
+    if let Some(has_numbers) = self.get_has_numbers() {
+      builder = builder.has_numbers(has_numbers);
+    }
+
+    if let Some(typing_mode) = self.get_typing_mode() {
+      builder = builder.typing_mode(typing_mode);
+    }
+
+    // Empty language selects snippets of any language
+    if let Some(code_lang) = self.code.filter(|code_lang| !code_lang.is_empty()) {
+      builder = builder.code_lang(code_lang);
+    }
+
+    if let Some(typing_duration) = self.duration {
+      builder = builder.typing_duration(typing_duration);
+    }
+
+    if let Some(layout_name) = self.layout {
+      builder = builder.layout_name(layout_name);
+    }
+
+    if let Some(lang_code) = self.language {
+      builder = builder.lang_code(lang_code);
+    }
+
+    builder
+  }
+}
+
+/// Returns the flag value set by the `--flag` / `--no-flag` pair
+fn get_explicit_flag(enabled: bool, disabled: bool) -> Option<bool> {
+  match (enabled, disabled) {
+    (true, _) => Some(true),
+    (_, true) => Some(false),
+    _ => None,
+  }
+}
+
+/// Parses a typing duration from the count of seconds
+fn parse_typing_duration(value: &str) -> Result<TypingDuration, String> {
+  let seconds = value
+    .parse::<usize>()
+    .map_err(|_| format!("`{value}` is not a number of seconds"))?;
+
+  TypingDuration::from_seconds(seconds)
+    .ok_or_else(|| String::from("duration must be at least 1 second"))
+}
+
+/// Parses a supported count of words
+fn parse_words_count(value: &str) -> Result<usize, String> {
+  let words_count = value
+    .parse::<usize>()
+    .map_err(|_| format!("`{value}` is not a number of words"))?;
+
+  if WORD_COUNTS.contains(&words_count) {
+    Ok(words_count)
+  } else {
+    Err(String::from(
+      "supported counts are 10, 25, 50 and 100 words",
+    ))
+  }
+}
+
+/// Validates the language code against the available dictionaries
+fn parse_lang_code(value: &str) -> Result<String, String> {
+  let lang_codes = Language::default().init().get_lang_codes();
+
+  if lang_codes.iter().any(|lang_code| lang_code == value) {
+    Ok(value.to_string())
+  } else {
+    Err(format!("available languages are {}", lang_codes.join(", ")))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn parse_without_arguments() {
+    let cli = TukaiCli::try_parse_from(["tukai"]).expect("Failed to parse empty arguments");
+
+    assert_eq!(cli.screen, ActiveScreenEnum::Typing);
+    assert!(cli.duration.is_none());
+    assert!(cli.language.is_none());
+    assert!(cli.get_has_transparent_bg().is_none());
+    assert!(cli.get_has_ghost().is_none());
+    assert!(cli.get_typing_mode().is_none());
+    assert_eq!(cli.tick_rate, DEFAULT_TICK_RATE_MS);
+  }
+
+  #[test]
+  fn parse_session_arguments() {
+    let cli = TukaiCli::try_parse_from([
+      "tukai",
+      "--screen",
+      "stats",
+      "-d",
+      "30",
+      "-l",
+      "fr",
+      "--layout",
+      "goblin",
+      "-t",
+      "--no-ghost",
+      "--punctuation",
+      "--no-numbers",
+      "--tick-rate",
+      "50",
+    ])
+    .expect("Failed to parse arguments");
+
+    assert_eq!(cli.screen, ActiveScreenEnum::Stats);
+    assert_eq!(cli.duration, Some(TypingDuration::ThirtySec));
+    assert_eq!(cli.language.as_deref(), Some("fr"));
+    assert_eq!(cli.layout, Some(TukaiLayoutName::Goblin));
+    assert_eq!(cli.get_has_transparent_bg(), Some(true));
+    assert_eq!(cli.get_has_ghost(), Some(false));
+    assert_eq!(cli.get_has_punctuation(), Some(true));
+    assert!(cli.get_has_capitalization().is_none());
+    assert_eq!(cli.get_has_numbers(), Some(false));
+    assert_eq!(cli.get_typing_mode(), Some(TypingMode::Time));
+    assert_eq!(cli.tick_rate, 50);
+
+    assert!(TukaiCli::try_parse_from(["tukai", "--tick-rate", "5"]).is_err());
+  }
+
+  #[test]
+  fn parse_typing_mode() {
+    let cli = TukaiCli::try_parse_from(["tukai", "-w", "50"]).expect("Failed to parse words");
+    assert_eq!(cli.get_typing_mode(), Some(TypingMode::Words(50)));
+
+    let cli = TukaiCli::try_parse_from(["tukai", "--zen"]).expect("Failed to parse zen");
+    assert_eq!(cli.get_typing_mode(), Some(TypingMode::Zen));
+
+    let cli = TukaiCli::try_parse_from(["tukai", "-q"]).expect("Failed to parse quote");
+    assert_eq!(cli.get_typing_mode(), Some(TypingMode::Quote));
+
+    let cli = TukaiCli::try_parse_from(["tukai", "--code", "--no-auto-indent"])
+      .expect("Failed to parse code");
+    assert_eq!(cli.get_typing_mode(), Some(TypingMode::Code));
+    assert_eq!(cli.code.as_deref(), Some(""));
+    assert_eq!(cli.get_has_auto_indent(), Some(false));
+
+    let cli = TukaiCli::try_parse_from(["tukai", "-c", "rust"]).expect("Failed to parse code");
+    assert_eq!(cli.code.as_deref(), Some("rust"));
+
+    let cli = TukaiCli::try_parse_from(["tukai", "-d", "90"]).expect("Failed to parse duration");
+    assert_eq!(cli.duration, Some(TypingDuration::Custom(90)));
+  }
+
+  #[test]
+  fn parse_export_command() {
+    let cli = TukaiCli::try_parse_from([
+      "tukai",
+      "export",
+      "-o",
+      "runs.json",
+      "--storage-path",
+      "work.bin",
+    ])
+    .expect("Failed to parse export command");
+
+    assert_eq!(cli.storage_path, Some(PathBuf::from("work.bin")));
+
+    let Some(TukaiCommand::Export { format, output }) = cli.command else {
+      panic!("Export command was not parsed");
+    };
+
+    assert!(format.is_none());
+    assert_eq!(output, Some(PathBuf::from("runs.json")));
+  }
+
+  #[test]
+  fn reject_invalid_arguments() {
+    assert!(TukaiCli::try_parse_from(["tukai", "-d", "0"]).is_err());
+    assert!(TukaiCli::try_parse_from(["tukai", "-l", "xx"]).is_err());
+    assert!(TukaiCli::try_parse_from(["tukai", "--transparent", "--no-transparent"]).is_err());
+    assert!(TukaiCli::try_parse_from(["tukai", "-w", "42"]).is_err());
+    assert!(TukaiCli::try_parse_from(["tukai", "-w", "10", "--zen"]).is_err());
+    assert!(TukaiCli::try_parse_from(["tukai", "--quote", "-d", "30"]).is_err());
+    assert!(TukaiCli::try_parse_from(["tukai", "-c", "go", "-q"]).is_err());
+    assert!(TukaiCli::try_parse_from(["tukai", "--numbers", "--no-numbers"]).is_err());
+  }
+}
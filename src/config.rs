@@ -5,11 +5,15 @@ use std::cell::{Ref, RefCell, RefMut};
 use serde::{Deserialize, Serialize};
 
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 use std::{collections::HashMap, fmt::Display, hash::Hash};
 
 use maplit::hashmap;
 use ratatui::style::Color;
 
+use crate::screens::ActiveScreenEnum;
+use crate::storage::storage_handler::StorageHandler;
+
 pub trait ToColor {
   /// Converts the `(u8, u8, u8)` tuple to a `Color::Rgb`
   ///
@@ -58,7 +62,7 @@ pub enum TukaiLayoutColorTypeEnum {
 /// Used for a switchable layout colors
 ///
 /// Switchable with a `ctrl-s` shortcut
-#[derive(PartialEq, Eq, Hash, Debug, Serialize, Deserialize, Clone)]
+#[derive(PartialEq, Eq, Hash, Debug, Serialize, Deserialize, Clone, clap::ValueEnum)]
 pub enum TukaiLayoutName {
   Iced,
   Rust,
@@ -267,9 +271,130 @@ impl TukaiLayout {
 #[folder = "dictionary/"]
 struct LanguageDictionary;
 
+#[derive(RustEmbed)]
+#[folder = "quotes/"]
+struct QuoteDictionary;
+
+/// Source of a language dictionary file
+enum LanguageFile {
+  // File embedded from the `dictionary` folder
+  Embedded(String),
+
+  // File embedded from the `quotes` folder
+  EmbeddedQuotes(String),
+
+  // File from the user's dictionary (or quotes) directory
+  Custom(PathBuf),
+}
+
+impl LanguageFile {
+  /// Returns the language code from the language file name
+  ///
+  /// i.e. `en.txt` -> `en`
+  fn get_lang_code(&self) -> String {
+    let path = match self {
+      LanguageFile::Embedded(filename) | LanguageFile::EmbeddedQuotes(filename) => {
+        Path::new(filename)
+      }
+      LanguageFile::Custom(path) => path.as_path(),
+    };
+
+    path
+      .file_stem()
+      .and_then(|s| s.to_str())
+      .unwrap_or("unknown")
+      .to_string()
+  }
+
+  /// Reads the content of the dictionary file
+  fn read_content(&self) -> Result<String, Box<dyn std::error::Error>> {
+    match self {
+      LanguageFile::Embedded(filename) => {
+        let file =
+          LanguageDictionary::get(filename).ok_or("Not found a language dictionary file")?;
+        Ok(String::from_utf8(file.data.into_owned())?)
+      }
+      LanguageFile::EmbeddedQuotes(filename) => {
+        let file = QuoteDictionary::get(filename).ok_or("Not found a quotes file")?;
+        Ok(String::from_utf8(file.data.into_owned())?)
+      }
+      LanguageFile::Custom(path) => Ok(std::fs::read_to_string(path)?),
+    }
+  }
+}
+
+/// Passage of the quote mode
+#[derive(Debug, Clone, PartialEq)]
+pub struct Quote {
+  // Text of the passage (single line)
+  text: String,
+
+  // Attribution (empty if unknown)
+  source: String,
+}
+
+impl Quote {
+  /// Parses the content of a quotes file
+  ///
+  /// Quotes are separated by an empty line, lines of a quote are joined.
+  /// The last line starting with `—` (or `--`) is the attribution, i.e.
+  ///
+  /// ```text
+  /// Knowledge is power.
+  /// — Francis Bacon
+  /// ```
+  pub fn parse_quotes(content: &str) -> Vec<Quote> {
+    let mut quotes = Vec::new();
+    let mut lines = Vec::new();
+
+    for line in content.lines().map(str::trim).chain(std::iter::once("")) {
+      if !line.is_empty() {
+        lines.push(line);
+        continue;
+      }
+
+      let source = match lines.last() {
+        Some(last_line) if last_line.starts_with('—') || last_line.starts_with("--") => lines
+          .pop()
+          .map(|source| source.trim_start_matches(['—', '-']).trim().to_string())
+          .unwrap_or_default(),
+        _ => String::new(),
+      };
+
+      if !lines.is_empty() {
+        quotes.push(Quote {
+          text: lines.join(" "),
+          source,
+        });
+      }
+
+      lines.clear();
+    }
+
+    quotes
+  }
+
+  pub fn get_text(&self) -> &str {
+    &self.text
+  }
+
+  pub fn get_source(&self) -> &str {
+    &self.source
+  }
+}
+
 pub struct Language {
-  // Language files paths from the `words` folder
-  language_files: Vec<String>,
+  // Language files from the `dictionary` folder and the user's dictionary directory
+  language_files: Vec<LanguageFile>,
+
+  // Quotes files from the `quotes` folder and the user's quotes directory
+  quote_files: Vec<LanguageFile>,
+
+  // Directory with the user's custom dictionaries
+  dictionary_dir_path: PathBuf,
+
+  // Directory with the user's custom quotes
+  quotes_dir_path: PathBuf,
 
   // Current used language index
   current_index: usize,
@@ -286,6 +411,9 @@ impl Language {
   pub fn default() -> Self {
     Self {
       language_files: Vec::new(),
+      quote_files: Vec::new(),
+      dictionary_dir_path: StorageHandler::get_local_dir_path().join("dictionary"),
+      quotes_dir_path: StorageHandler::get_local_dir_path().join("quotes"),
       current_index: 0,
       lang_code: String::from("en"),
       words: Vec::new(),
@@ -293,46 +421,74 @@ impl Language {
   }
 
   pub fn init_lang_code(&mut self) {
-    let filename = &self.language_files[self.current_index];
-
-    let lang_code = Path::new(filename)
-      .file_stem()
-      .and_then(|s| s.to_str())
-      .unwrap_or("unknown")
-      .to_string();
-
-    self.lang_code = lang_code;
+    self.lang_code = self.language_files[self.current_index].get_lang_code();
   }
 
-  /// Load language files from the `words` folder
+  /// Load language files from the `dictionary` folder
+  /// and the user's dictionary directory
+  ///
+  /// Loads the quotes files as well.
   pub fn init(mut self) -> Self {
-    if let Ok(language_files) = self.load_language_files() {
-      self.language_files = language_files;
+    self.language_files = self.load_language_files();
+    self.quote_files = self.load_quote_files();
+
+    if self.language_files.is_empty() {
+      return self;
     }
 
+    self.init_lang_code();
+
     // If language dictionary files were founded
     // Sets the words
-    if !self.language_files.is_empty()
-      && let Ok(words) = self.load_language_words()
-    {
+    if let Ok(words) = self.load_language_words() {
       self.words = words;
     }
 
     self
   }
 
+  /// Sets a directory with the user's custom dictionaries
+  #[allow(unused)]
+  pub fn dictionary_dir_path<P: AsRef<Path>>(mut self, dictionary_dir_path: P) -> Self {
+    self.dictionary_dir_path = dictionary_dir_path.as_ref().to_path_buf();
+    self
+  }
+
+  /// Sets a directory with the user's custom quotes
+  #[allow(unused)]
+  pub fn quotes_dir_path<P: AsRef<Path>>(mut self, quotes_dir_path: P) -> Self {
+    self.quotes_dir_path = quotes_dir_path.as_ref().to_path_buf();
+    self
+  }
+
   pub fn current_index(&mut self, index: usize) {
     self.current_index = index;
     self.init_lang_code();
   }
 
+  /// Sets a current language by the language code
+  ///
+  /// Returns false if no language file has that code.
+  pub fn current_lang_code(&mut self, lang_code: &str) -> bool {
+    let index = self
+      .language_files
+      .iter()
+      .position(|language_file| language_file.get_lang_code() == lang_code);
+
+    if let Some(index) = index {
+      self.current_index(index);
+    }
+
+    index.is_some()
+  }
+
   #[allow(unused)]
   pub fn get_current_index(&self) -> &usize {
     &self.current_index
   }
 
-  /// Switches a current language
-  pub fn switch_language(&mut self) -> usize {
+  /// Switches a current language, then returns its language code
+  pub fn switch_language(&mut self) -> String {
     self.current_index += 1;
 
     if self.current_index >= self.language_files.len() {
@@ -340,40 +496,93 @@ impl Language {
     }
 
     self.init_lang_code();
-    self.current_index
+    self.lang_code.clone()
   }
 
-  /// Returns the paths of all available language files in the `words` folder.
+  /// Returns the language code of the embedded dictionary at the index
+  pub fn get_embedded_lang_code(index: usize) -> Option<String> {
+    LanguageDictionary::iter()
+      .nth(index)
+      .map(|file| LanguageFile::Embedded(file.to_string()).get_lang_code())
+  }
+
+  /// Returns all available language files.
   ///
-  /// So i.e. available languages
-  pub fn load_language_files(&self) -> Result<Vec<String>, Box<dyn std::error::Error>> {
-    let languages = LanguageDictionary::iter()
-      .map(|file| file.to_string())
-      .collect::<Vec<String>>();
+  /// The embedded dictionaries go first, followed by the `.txt` files
+  /// from the user's dictionary directory (sorted by name).
+  /// A custom file with the same language code replaces the embedded one.
+  fn load_language_files(&self) -> Vec<LanguageFile> {
+    let language_files = LanguageDictionary::iter()
+      .map(|file| LanguageFile::Embedded(file.to_string()))
+      .collect::<Vec<LanguageFile>>();
+
+    Language::merge_custom_files(language_files, &self.dictionary_dir_path)
+  }
+
+  /// Returns all available quotes files.
+  ///
+  /// Same as the language files, but from the `quotes` folder
+  /// and the user's quotes directory.
+  fn load_quote_files(&self) -> Vec<LanguageFile> {
+    let quote_files = QuoteDictionary::iter()
+      .map(|file| LanguageFile::EmbeddedQuotes(file.to_string()))
+      .collect::<Vec<LanguageFile>>();
+
+    Language::merge_custom_files(quote_files, &self.quotes_dir_path)
+  }
+
+  /// Adds the `.txt` files from the directory to the embedded files.
+  ///
+  /// A custom file with the same language code replaces the embedded one.
+  fn merge_custom_files(
+    mut language_files: Vec<LanguageFile>,
+    dir_path: &Path,
+  ) -> Vec<LanguageFile> {
+    for custom_file in Language::load_custom_files(dir_path) {
+      let lang_code = custom_file.get_lang_code();
+
+      match language_files
+        .iter()
+        .position(|language_file| language_file.get_lang_code() == lang_code)
+      {
+        Some(index) => language_files[index] = custom_file,
+        None => language_files.push(custom_file),
+      }
+    }
+
+    language_files
+  }
+
+  /// Returns the `.txt` files from the user's directory.
+  ///
+  /// If the directory does not exist, returns an empty vector.
+  fn load_custom_files(dir_path: &Path) -> Vec<LanguageFile> {
+    let Ok(entries) = std::fs::read_dir(dir_path) else {
+      return Vec::new();
+    };
+
+    let mut paths = entries
+      .filter_map(|entry| entry.ok())
+      .map(|entry| entry.path())
+      .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "txt"))
+      .collect::<Vec<PathBuf>>();
 
-    // Maybe in feature manual load custom languages
-    //
-    // let languages = entries
-    //   .filter_map(|entry| entry.ok())
-    //   .filter(|entry| entry.path().is_file())
-    //   .map(|entry| entry.path())
-    //   .collect::<Vec<PathBuf>>();
+    paths.sort();
 
-    Ok(languages)
+    paths.into_iter().map(LanguageFile::Custom).collect()
   }
 
   /// Returns current selected languages words from the language file
   ///
   /// So i.e. language words
   pub fn load_language_words(&self) -> Result<Vec<String>, Box<dyn std::error::Error>> {
-    let language_file_path = self
+    let language_file = self
       .language_files
       .get(self.current_index)
       .ok_or("Not found a language dictionary file")?;
 
-    let file = LanguageDictionary::get(language_file_path).unwrap();
-
-    let words = std::str::from_utf8(&file.data)?
+    let words = language_file
+      .read_content()?
       .lines()
       .flat_map(|line| {
         line
@@ -386,12 +595,34 @@ impl Language {
     Ok(words)
   }
 
+  /// Returns the quotes of the current language
+  ///
+  /// Fails if the language has no quotes file.
+  pub fn load_quotes(&self) -> Result<Vec<Quote>, Box<dyn std::error::Error>> {
+    let quote_file = self
+      .quote_files
+      .iter()
+      .find(|quote_file| quote_file.get_lang_code() == self.lang_code)
+      .ok_or("Not found a quotes file")?;
+
+    Ok(Quote::parse_quotes(&quote_file.read_content()?))
+  }
+
   pub fn get_lang_code(&self) -> &String {
     &self.lang_code
   }
+
+  /// Returns codes of all available languages
+  pub fn get_lang_codes(&self) -> Vec<String> {
+    self
+      .language_files
+      .iter()
+      .map(|language_file| language_file.get_lang_code())
+      .collect()
+  }
 }
 
-#[derive(Serialize, Deserialize, Hash, PartialEq, Eq, Debug, Clone)]
+#[derive(Serialize, Deserialize, Hash, PartialEq, Eq, Debug, Clone, Default)]
 /// Represents the available durations for the test
 ///
 /// This enum defines default durations
@@ -400,20 +631,33 @@ impl Language {
 /// - `ThirtySec` - 30 seconds typing duration
 /// - `Minute` - 60 seconds typing duration
 /// - `ThreeMinutes` - 180 seconds typing duration
+/// - `Custom` - any other count of seconds
 pub enum TypingDuration {
   FifteenSec,
   ThirtySec,
+  #[default]
   Minute,
   ThreeMinutes,
+  Custom(usize),
 }
 
-impl Default for TypingDuration {
-  fn default() -> Self {
-    Self::Minute
+impl TypingDuration {
+  /// Returns the typing duration matching the count of seconds
+  ///
+  /// Non-standard counts are `Custom`, None for zero seconds.
+  pub fn from_seconds(seconds: usize) -> Option<Self> {
+    use TypingDuration::*;
+
+    match seconds {
+      0 => None,
+      15 => Some(FifteenSec),
+      30 => Some(ThirtySec),
+      60 => Some(Minute),
+      180 => Some(ThreeMinutes),
+      _ => Some(Custom(seconds)),
+    }
   }
-}
 
-impl TypingDuration {
   pub fn as_seconds(&self) -> usize {
     use TypingDuration::*;
 
@@ -422,6 +666,90 @@ impl TypingDuration {
       ThirtySec => 30,
       Minute => 60,
       ThreeMinutes => 180,
+      Custom(seconds) => *seconds,
+    }
+  }
+
+  pub fn as_duration(&self) -> Duration {
+    Duration::from_secs(self.as_seconds() as u64)
+  }
+}
+
+/// Available word counts of the `TypingMode::Words`
+pub const WORD_COUNTS: [usize; 4] = [10, 25, 50, 100];
+
+/// Count of the words generated at once in the `TypingMode::Zen`
+pub const ZEN_WORDS_COUNT: usize = 50;
+
+/// Represents the type of the test
+///
+/// Saved as a text (i.e. `time`, `25 words`, `zen`),
+/// so it stays readable in the exports.
+///
+/// # Variants
+/// - `Time` - runs until the typing duration is over
+/// - `Words` - runs until the count of words is typed
+/// - `Zen` - keeps appending words until the run is finished by the user
+/// - `Quote` - runs until the quote of the language is typed
+/// - `Code` - runs until the code snippet is typed
+#[derive(Serialize, Deserialize, Hash, PartialEq, Eq, Debug, Clone, Copy, Default)]
+#[serde(into = "String", try_from = "String")]
+pub enum TypingMode {
+  #[default]
+  Time,
+  Words(usize),
+  Zen,
+  Quote,
+  Code,
+}
+
+impl TypingMode {
+  /// Returns the count of the words generated for the run
+  ///
+  /// Quote and code runs fall back to the words of a timed run,
+  /// if there is no quote (or snippet).
+  pub fn get_words_count(&self, typing_duration: &TypingDuration) -> usize {
+    match self {
+      TypingMode::Time | TypingMode::Quote | TypingMode::Code => typing_duration.as_seconds() * 2,
+      TypingMode::Words(words_count) => *words_count,
+      TypingMode::Zen => ZEN_WORDS_COUNT,
+    }
+  }
+}
+
+impl Display for TypingMode {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      TypingMode::Time => write!(f, "time"),
+      TypingMode::Words(words_count) => write!(f, "{words_count} words"),
+      TypingMode::Zen => write!(f, "zen"),
+      TypingMode::Quote => write!(f, "quote"),
+      TypingMode::Code => write!(f, "code"),
+    }
+  }
+}
+
+impl From<TypingMode> for String {
+  fn from(typing_mode: TypingMode) -> Self {
+    typing_mode.to_string()
+  }
+}
+
+impl TryFrom<String> for TypingMode {
+  type Error = String;
+
+  fn try_from(value: String) -> Result<Self, Self::Error> {
+    match value.as_str() {
+      "time" => Ok(TypingMode::Time),
+      "zen" => Ok(TypingMode::Zen),
+      "quote" => Ok(TypingMode::Quote),
+      "code" => Ok(TypingMode::Code),
+      _ => value
+        .strip_suffix(" words")
+        .and_then(|words_count| words_count.parse::<usize>().ok())
+        .filter(|words_count| *words_count > 0)
+        .map(TypingMode::Words)
+        .ok_or_else(|| format!("Unsupported typing mode {value}")),
     }
   }
 }
@@ -432,10 +760,18 @@ pub trait ConfigBuilder<T> {
   fn build(self) -> T;
 }
 
+/// Default storage file path
+///
+/// Relative to the OS's local data directory (refer to the `StorageHandler`)
+const DEFAULT_FILE_PATH: &str = "tukai.bin";
+
 pub struct TukaiConfig {
   // Path to the storage file
   file_path: PathBuf,
 
+  // Screen displayed after the start
+  screen: ActiveScreenEnum,
+
   // Choosen layout
   layout: RefCell<TukaiLayout>,
 
@@ -445,18 +781,47 @@ pub struct TukaiConfig {
   // App background is transparent
   pub has_transparent_bg: bool,
 
+  // Ghost cursor of the personal best is displayed
+  pub has_ghost: bool,
+
+  // Leading indentation of the code lines is skipped
+  pub has_auto_indent: bool,
+
+  // Generated text contains punctuation
+  pub has_punctuation: bool,
+
+  // Generated sentences start with a capital letter
+  pub has_capitalization: bool,
+
+  // Generated text contains numbers
+  pub has_numbers: bool,
+
   // Typing duration
   pub typing_duration: TypingDuration,
+
+  // Type of the test
+  pub typing_mode: TypingMode,
+
+  // Programming language of the code snippets (any if not set)
+  pub code_lang: Option<String>,
 }
 
 impl TukaiConfig {
   pub fn default() -> Self {
     Self {
-      file_path: PathBuf::from("tukai.bin"),
+      file_path: PathBuf::from(DEFAULT_FILE_PATH),
+      screen: ActiveScreenEnum::Typing,
       layout: RefCell::new(TukaiLayout::default()),
       language: RefCell::new(Language::default().init()),
       has_transparent_bg: false,
+      has_ghost: false,
+      has_auto_indent: true,
+      has_punctuation: false,
+      has_capitalization: false,
+      has_numbers: false,
       typing_duration: TypingDuration::default(),
+      typing_mode: TypingMode::default(),
+      code_lang: None,
     }
   }
 
@@ -480,6 +845,10 @@ impl TukaiConfig {
     &self.file_path
   }
 
+  pub fn get_screen(&self) -> ActiveScreenEnum {
+    self.screen
+  }
+
   /// Toggles the background between transparent and the layout color.
   ///
   /// Flips the `has_transparent_bg` flag and returns the updated state.
@@ -491,6 +860,61 @@ impl TukaiConfig {
     self.has_transparent_bg
   }
 
+  /// Toggles the ghost cursor of the personal best.
+  ///
+  /// Returns the updated state.
+  pub fn toggle_ghost(&mut self) -> bool {
+    self.has_ghost = !self.has_ghost;
+    self.has_ghost
+  }
+
+  /// Toggles skipping of the leading indentation in the code mode.
+  ///
+  /// Returns the updated state.
+  pub fn toggle_auto_indent(&mut self) -> bool {
+    self.has_auto_indent = !self.has_auto_indent;
+    self.has_auto_indent
+  }
+
+  /// Toggles punctuation of the generated text.
+  ///
+  /// Returns the updated state.
+  pub fn toggle_punctuation(&mut self) -> bool {
+    self.has_punctuation = !self.has_punctuation;
+    self.has_punctuation
+  }
+
+  /// Toggles capitalization of the generated sentences.
+  ///
+  /// Returns the updated state.
+  pub fn toggle_capitalization(&mut self) -> bool {
+    self.has_capitalization = !self.has_capitalization;
+    self.has_capitalization
+  }
+
+  /// Toggles numbers in the generated text.
+  ///
+  /// Returns the updated state.
+  pub fn toggle_numbers(&mut self) -> bool {
+    self.has_numbers = !self.has_numbers;
+    self.has_numbers
+  }
+
+  /// Returns the enabled options of the generated text
+  /// (i.e. `!? Aa 123`), None if all are disabled.
+  pub fn get_text_options_pretty(&self) -> Option<String> {
+    let text_options = [
+      (self.has_punctuation, "!?"),
+      (self.has_capitalization, "Aa"),
+      (self.has_numbers, "123"),
+    ]
+    .into_iter()
+    .filter_map(|(is_enabled, symbol)| is_enabled.then_some(symbol))
+    .collect::<Vec<&str>>();
+
+    (!text_options.is_empty()).then(|| text_options.join(" "))
+  }
+
   /// Switches the typing duration.
   ///
   /// Options:
@@ -498,10 +922,12 @@ impl TukaiConfig {
   /// 2. Three minutes
   /// 3. Fifteen seconds
   /// 4. Thirty seconds
+  ///
+  /// A custom duration switches to the fifteen seconds.
   pub fn switch_typing_duration(&mut self) -> TypingDuration {
     self.typing_duration = match self.typing_duration {
       TypingDuration::Minute => TypingDuration::ThreeMinutes,
-      TypingDuration::ThreeMinutes => TypingDuration::FifteenSec,
+      TypingDuration::ThreeMinutes | TypingDuration::Custom(_) => TypingDuration::FifteenSec,
       TypingDuration::FifteenSec => TypingDuration::ThirtySec,
       TypingDuration::ThirtySec => TypingDuration::Minute,
     };
@@ -509,6 +935,42 @@ impl TukaiConfig {
     self.typing_duration.clone()
   }
 
+  /// Switches the type of the test.
+  ///
+  /// Options:
+  /// 1. Time
+  /// 2. Words (25 words)
+  /// 3. Quote
+  /// 4. Code
+  /// 5. Zen
+  pub fn switch_typing_mode(&mut self) -> TypingMode {
+    self.typing_mode = match self.typing_mode {
+      TypingMode::Time => TypingMode::Words(WORD_COUNTS[1]),
+      TypingMode::Words(_) => TypingMode::Quote,
+      TypingMode::Quote => TypingMode::Code,
+      TypingMode::Code => TypingMode::Zen,
+      TypingMode::Zen => TypingMode::Time,
+    };
+
+    self.typing_mode
+  }
+
+  /// Switches the word count of the `TypingMode::Words`.
+  ///
+  /// Cycles through the `WORD_COUNTS`, other modes are kept.
+  pub fn switch_words_count(&mut self) -> TypingMode {
+    if let TypingMode::Words(words_count) = self.typing_mode {
+      let next_index = WORD_COUNTS
+        .iter()
+        .position(|count| *count == words_count)
+        .map_or(0, |index| (index + 1) % WORD_COUNTS.len());
+
+      self.typing_mode = TypingMode::Words(WORD_COUNTS[next_index]);
+    }
+
+    self.typing_mode
+  }
+
   /// Returns the background color of the selected layout.
   ///
   /// If `has_transparent_bg` is `true`, no background color is applied.
@@ -526,53 +988,345 @@ pub struct TukaiConfigBuilder {
   // Path to the `language file`
   file_path: Option<PathBuf>,
 
+  // Screen displayed after the start
+  screen: Option<ActiveScreenEnum>,
+
   // Selected layout
   layout: Option<RefCell<TukaiLayout>>,
 
+  // Selected layout name
+  layout_name: Option<TukaiLayoutName>,
+
   // Selected language
   language: Option<RefCell<Language>>,
 
+  // Selected language code
+  lang_code: Option<String>,
+
   // Has application background transparent
-  has_transparent_bg: bool,
+  has_transparent_bg: Option<bool>,
+
+  // Has the ghost cursor of the personal best
+  has_ghost: Option<bool>,
+
+  // Skips the leading indentation of the code lines
+  has_auto_indent: Option<bool>,
+
+  // Adds punctuation into the generated text
+  has_punctuation: Option<bool>,
+
+  // Capitalizes the generated sentences
+  has_capitalization: Option<bool>,
+
+  // Adds numbers into the generated text
+  has_numbers: Option<bool>,
 
   // Typing duration per run
   typing_duration: Option<TypingDuration>,
+
+  // Type of the test
+  typing_mode: Option<TypingMode>,
+
+  // Programming language of the code snippets
+  code_lang: Option<String>,
 }
 
 impl TukaiConfigBuilder {
   pub fn new() -> Self {
     Self {
       file_path: None,
+      screen: None,
       layout: None,
+      layout_name: None,
       language: None,
-      has_transparent_bg: true,
+      lang_code: None,
+      has_transparent_bg: None,
+      has_ghost: None,
+      has_auto_indent: None,
+      has_punctuation: None,
+      has_capitalization: None,
+      has_numbers: None,
       typing_duration: None,
+      typing_mode: None,
+      code_lang: None,
     }
   }
 
-  #[allow(unused)]
+  /// Returns the storage file path
+  pub fn get_file_path(&self) -> PathBuf {
+    self
+      .file_path
+      .clone()
+      .unwrap_or_else(|| PathBuf::from(DEFAULT_FILE_PATH))
+  }
+
   pub fn file_path<P: AsRef<Path>>(mut self, file_path: P) -> Self {
     self.file_path = Some(file_path.as_ref().to_path_buf());
     self
   }
 
+  pub fn screen(mut self, screen: ActiveScreenEnum) -> Self {
+    self.screen = Some(screen);
+    self
+  }
+
   #[allow(unused)]
   pub fn layout(mut self, layout: TukaiLayout) -> Self {
     self.layout = Some(RefCell::new(layout));
     self
   }
 
+  pub fn layout_name(mut self, layout_name: TukaiLayoutName) -> Self {
+    self.layout_name = Some(layout_name);
+    self
+  }
+
+  pub fn lang_code<S: Into<String>>(mut self, lang_code: S) -> Self {
+    self.lang_code = Some(lang_code.into());
+    self
+  }
+
+  pub fn has_transparent_bg(mut self, has_transparent_bg: bool) -> Self {
+    self.has_transparent_bg = Some(has_transparent_bg);
+    self
+  }
+
+  pub fn has_ghost(mut self, has_ghost: bool) -> Self {
+    self.has_ghost = Some(has_ghost);
+    self
+  }
+
+  pub fn has_auto_indent(mut self, has_auto_indent: bool) -> Self {
+    self.has_auto_indent = Some(has_auto_indent);
+    self
+  }
+
+  pub fn has_punctuation(mut self, has_punctuation: bool) -> Self {
+    self.has_punctuation = Some(has_punctuation);
+    self
+  }
+
+  pub fn has_capitalization(mut self, has_capitalization: bool) -> Self {
+    self.has_capitalization = Some(has_capitalization);
+    self
+  }
+
+  pub fn has_numbers(mut self, has_numbers: bool) -> Self {
+    self.has_numbers = Some(has_numbers);
+    self
+  }
+
+  pub fn code_lang<S: Into<String>>(mut self, code_lang: S) -> Self {
+    self.code_lang = Some(code_lang.into());
+    self
+  }
+
+  pub fn typing_duration(mut self, typing_duration: TypingDuration) -> Self {
+    self.typing_duration = Some(typing_duration);
+    self
+  }
+
+  pub fn typing_mode(mut self, typing_mode: TypingMode) -> Self {
+    self.typing_mode = Some(typing_mode);
+    self
+  }
+
+  /// Fills the values which were not set explicitly
+  /// with the values saved in the storage.
+  ///
+  /// Explicitly set values (i.e. from the command line) take precedence.
+  pub fn storage_fallback(mut self, storage_handler: &StorageHandler) -> Self {
+    self
+      .typing_duration
+      .get_or_insert_with(|| storage_handler.get_typing_duration());
+
+    self
+      .typing_mode
+      .get_or_insert_with(|| storage_handler.get_typing_mode());
+
+    self
+      .layout_name
+      .get_or_insert_with(|| storage_handler.get_layout_name());
+
+    self
+      .has_transparent_bg
+      .get_or_insert_with(|| storage_handler.get_has_transparent_bg());
+
+    self
+      .has_ghost
+      .get_or_insert_with(|| storage_handler.get_has_ghost());
+
+    self
+      .has_auto_indent
+      .get_or_insert_with(|| storage_handler.get_has_auto_indent());
+
+    self
+      .has_punctuation
+      .get_or_insert_with(|| storage_handler.get_has_punctuation());
+
+    self
+      .has_capitalization
+      .get_or_insert_with(|| storage_handler.get_has_capitalization());
+
+    self
+      .has_numbers
+      .get_or_insert_with(|| storage_handler.get_has_numbers());
+
+    self
+      .lang_code
+      .get_or_insert_with(|| storage_handler.get_lang_code());
+
+    self
+  }
+
   pub fn build(self) -> TukaiConfig {
     let config_default = TukaiConfig::default();
 
+    let layout = self.layout.unwrap_or(config_default.layout);
+
+    if let Some(layout_name) = self.layout_name {
+      layout.borrow_mut().active_layout_name(layout_name);
+    }
+
+    let language = self.language.unwrap_or(config_default.language);
+
+    // If the language does not exist anymore, the first language is kept
+    if let Some(lang_code) = &self.lang_code {
+      language.borrow_mut().current_lang_code(lang_code);
+    }
+
     TukaiConfig {
       file_path: self.file_path.unwrap_or(config_default.file_path),
-      layout: self.layout.unwrap_or(config_default.layout),
-      language: self.language.unwrap_or(config_default.language),
-      has_transparent_bg: self.has_transparent_bg,
+      screen: self.screen.unwrap_or(config_default.screen),
+      layout,
+      language,
+      has_transparent_bg: self
+        .has_transparent_bg
+        .unwrap_or(config_default.has_transparent_bg),
+      has_ghost: self.has_ghost.unwrap_or(config_default.has_ghost),
+      has_auto_indent: self
+        .has_auto_indent
+        .unwrap_or(config_default.has_auto_indent),
+      has_punctuation: self
+        .has_punctuation
+        .unwrap_or(config_default.has_punctuation),
+      has_capitalization: self
+        .has_capitalization
+        .unwrap_or(config_default.has_capitalization),
+      has_numbers: self.has_numbers.unwrap_or(config_default.has_numbers),
       typing_duration: self
         .typing_duration
         .unwrap_or(config_default.typing_duration),
+      typing_mode: self.typing_mode.unwrap_or(config_default.typing_mode),
+      code_lang: self.code_lang,
     }
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use uuid::Uuid;
+
+  #[test]
+  fn typing_duration_from_seconds() {
+    assert_eq!(
+      TypingDuration::from_seconds(30),
+      Some(TypingDuration::ThirtySec)
+    );
+    assert_eq!(
+      TypingDuration::from_seconds(42),
+      Some(TypingDuration::Custom(42))
+    );
+    assert_eq!(TypingDuration::from_seconds(0), None);
+    assert_eq!(TypingDuration::Custom(42).as_seconds(), 42);
+  }
+
+  #[test]
+  fn typing_mode_from_text() {
+    for typing_mode in [
+      TypingMode::Time,
+      TypingMode::Words(25),
+      TypingMode::Zen,
+      TypingMode::Quote,
+      TypingMode::Code,
+    ] {
+      assert_eq!(
+        TypingMode::try_from(typing_mode.to_string()),
+        Ok(typing_mode)
+      );
+    }
+
+    assert!(TypingMode::try_from(String::from("0 words")).is_err());
+    assert!(TypingMode::try_from(String::from("marathon")).is_err());
+  }
+
+  #[test]
+  fn switch_words_count() {
+    let mut config = TukaiConfig::default();
+    assert_eq!(config.switch_words_count(), TypingMode::Time);
+
+    config.typing_mode = TypingMode::Words(100);
+    assert_eq!(config.switch_words_count(), TypingMode::Words(10));
+  }
+
+  #[test]
+  fn parse_quotes() {
+    let quotes = Quote::parse_quotes(
+      "Knowledge is power.\n— Francis Bacon\n\n\nAll the world's a stage,\n  and all the men\n\nAnonymous words.\n",
+    );
+
+    assert_eq!(quotes.len(), 3);
+    assert_eq!(quotes[0].get_text(), "Knowledge is power.");
+    assert_eq!(quotes[0].get_source(), "Francis Bacon");
+    assert_eq!(
+      quotes[1].get_text(),
+      "All the world's a stage, and all the men"
+    );
+    assert_eq!(quotes[2].get_source(), "");
+  }
+
+  #[test]
+  fn load_embedded_quotes() {
+    let mut language = Language::default().init();
+    assert!(language.current_lang_code("en"));
+
+    let quotes = language.load_quotes().expect("Failed to load the quotes");
+    assert!(quotes.iter().all(|quote| !quote.get_source().is_empty()));
+  }
+
+  #[test]
+  fn load_custom_dictionaries() {
+    let dictionary_dir_path = std::env::temp_dir().join(format!("tukai-{}", Uuid::new_v4()));
+    std::fs::create_dir_all(&dictionary_dir_path).expect("Failed to create dictionary directory");
+
+    std::fs::write(dictionary_dir_path.join("rust.txt"), "fn impl\nmatch trait")
+      .expect("Failed to write custom dictionary");
+    std::fs::write(dictionary_dir_path.join("en.txt"), "custom english")
+      .expect("Failed to write custom dictionary");
+    std::fs::write(dictionary_dir_path.join("notes.md"), "ignored")
+      .expect("Failed to write ignored file");
+
+    let mut language = Language::default()
+      .dictionary_dir_path(&dictionary_dir_path)
+      .init();
+
+    let lang_codes = language.get_lang_codes();
+    assert_eq!(lang_codes.first().map(String::as_str), Some("en"));
+    assert_eq!(lang_codes.last().map(String::as_str), Some("rust"));
+    assert!(!lang_codes.contains(&String::from("notes")));
+
+    assert_eq!(
+      language.load_language_words().unwrap(),
+      vec!["custom", "english"]
+    );
+
+    assert!(language.current_lang_code("rust"));
+    assert_eq!(
+      language.load_language_words().unwrap(),
+      vec!["fn", "impl", "match", "trait"]
+    );
+
+    std::fs::remove_dir_all(&dictionary_dir_path).expect("Failed to delete dictionary directory");
+  }
+}
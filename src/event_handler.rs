@@ -12,14 +12,19 @@ use futures::{FutureExt, StreamExt};
 
 /// Represents events generated by the Tukai application.
 ///
-/// - `Tick`: A periodic timer event used for time remainder on the typing screen.
+/// - `Tick`: A periodic timer event used for the time remainder and live stats on the typing screen.
+/// - `Frame`: A frequent timer event used for animations (i.e. a replay).
 /// - `Key`: An event representing a keyboard input, resolution change, wrapping a [`KeyEvent`].
 #[derive(Clone, Copy, Debug)]
 pub enum TukaiEvent {
   Tick,
+  Frame,
   Key(KeyEvent),
 }
 
+/// Default interval of the `TukaiEvent::Tick` (milliseconds)
+pub const DEFAULT_TICK_RATE_MS: u64 = 200;
+
 /// Handles sending and receiving `TukaiEvent`s asynchronously.
 ///
 /// This struct wraps an unbounded channel sender and receiver pair,
@@ -35,11 +40,12 @@ pub struct EventHandler {
 impl EventHandler {
   /// Spawns a background asynchronous task that:
   /// - Listens for terminal input events and forwards keyboard events as `TukaiEvent::Key`.
-  /// - Sends periodic `TukaiEvent::Tick` events every second.
+  /// - Sends periodic `TukaiEvent::Tick` events every `tick_rate`.
+  /// - Sends periodic `TukaiEvent::Frame` events every 50 milliseconds.
   ///
   /// The event loop uses Tokio’s async runtime and crossterm’s `EventStream` to handle input.
-  pub fn new() -> Self {
-    let tick_rate = Duration::from_secs(1);
+  pub fn new(tick_rate: Duration) -> Self {
+    let frame_rate = Duration::from_millis(50);
     let (_tx, rx) = mpsc::unbounded_channel::<TukaiEvent>();
 
     let tx_clone = _tx.clone();
@@ -47,9 +53,11 @@ impl EventHandler {
     tokio::spawn(async move {
       let mut reader = EventStream::new();
       let mut interval = tokio::time::interval(tick_rate);
+      let mut frame_interval = tokio::time::interval(frame_rate);
 
       loop {
         let tick_delay = interval.tick();
+        let frame_delay = frame_interval.tick();
         let crossterm_event = reader.next().fuse();
 
         tokio::select! {
@@ -67,6 +75,9 @@ impl EventHandler {
           _ = tick_delay => {
             tx_clone.send(TukaiEvent::Tick).unwrap();
           },
+          _ = frame_delay => {
+            tx_clone.send(TukaiEvent::Frame).unwrap();
+          },
         }
       }
     });
@@ -1,44 +1,38 @@
 use std::{
-  fs::{File, OpenOptions, create_dir_all},
+  ffi::OsString,
+  fs::{File, create_dir_all},
   io::{Read, Write},
-  path::Path,
+  path::{Path, PathBuf},
 };
 
-use anyhow::{Error, Result};
+use anyhow::Result;
 
 pub struct FileHandler;
 
 impl FileHandler {
-  /// Opens a file for reading and writing, creating it if it does not exist.
+  /// Returns a path next to the file with the appended extension.
   ///
-  /// If the file at the specified path exists, it is opened with read/write access.
-  /// If it does not exist, a new file is created.
-  ///
-  /// # Parameters
-  /// - `path`: A path to the file to open or create.
-  ///
-  /// # Returns
-  /// A [`Result`] containing the opened [`File`] on success, or an error on failure.
-  #[allow(clippy::suspicious_open_options)]
-  fn open_file<P: AsRef<Path>>(path: P) -> Result<File> {
-    let path_buf = path.as_ref().to_path_buf();
+  /// i.e. `tukai.bin` -> `tukai.bin.bak`
+  fn get_sibling_path<P: AsRef<Path>>(path: P, extension: &str) -> PathBuf {
+    let path = path.as_ref();
 
-    if let Some(parent_dir) = path_buf.parent() {
-      create_dir_all(parent_dir)?;
-    }
+    let mut file_name = path.file_name().map(OsString::from).unwrap_or_default();
+
+    file_name.push(format!(".{extension}"));
 
-    OpenOptions::new()
-      .read(true)
-      .write(true)
-      .create(true)
-      .open(path)
-      .map_err(Error::from)
+    path.with_file_name(file_name)
+  }
+
+  /// Returns the path to the backup of the file.
+  pub fn get_backup_file_path<P: AsRef<Path>>(path: P) -> PathBuf {
+    FileHandler::get_sibling_path(path, "bak")
   }
 
   /// Writes the given bytes into a file at the specified path.
   ///
-  /// This function opens the file (creating it if it doesn’t exist),
-  /// writes all the provided bytes to it, and then closes the file.
+  /// The bytes are written into a temporary file in the same directory,
+  /// synced to the disk, and then the temporary file is renamed over the target.
+  /// So the target contains either the previous or the new content, even if a crash occurs.
   ///
   /// # Parameters
   /// - `path`: The path to the file where the bytes will be written.
@@ -46,10 +40,30 @@ impl FileHandler {
   ///
   /// # Returns
   /// A [`Result`] which is [`Ok`] if the operation succeeds,
-  /// or an error if opening or writing to the file fails.
+  /// or an error if writing or renaming of the file fails.
   pub fn write_bytes_into_file<P: AsRef<Path>>(path: P, bytes: &[u8]) -> Result<()> {
-    let mut file = FileHandler::open_file(path)?;
-    file.write_all(bytes)?;
+    let path = path.as_ref();
+
+    if let Some(parent_dir) = path.parent() {
+      create_dir_all(parent_dir)?;
+    }
+
+    let temp_file_path = FileHandler::get_sibling_path(path, "tmp");
+
+    {
+      let mut temp_file = File::create(&temp_file_path)?;
+      temp_file.write_all(bytes)?;
+      temp_file.sync_all()?;
+    }
+
+    std::fs::rename(&temp_file_path, path)?;
+
+    // Makes the rename durable
+    #[cfg(unix)]
+    if let Some(parent_dir) = path.parent() {
+      File::open(parent_dir)?.sync_all()?;
+    }
+
     Ok(())
   }
 
@@ -66,7 +80,7 @@ impl FileHandler {
   /// or an error if the file could not be opened or read.
   pub fn read_bytes_from_file<P: AsRef<Path>>(path: P) -> Result<Vec<u8>> {
     let mut bytes_buf = Vec::new();
-    let mut file = FileHandler::open_file(path)?;
+    let mut file = File::open(path)?;
     file.read_to_end(&mut bytes_buf)?;
     Ok(bytes_buf)
   }
@@ -0,0 +1,114 @@
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Positions in a text counted in graphemes
+///
+/// The typing cursor moves by graphemes (user-perceived characters),
+/// so an accented letter takes a single position,
+/// even if it is composed of multiple chars (i.e. `e` and the combining acute accent).
+pub trait GraphemeText {
+  /// Returns the graphemes of the text
+  fn get_graphemes(&self) -> Vec<&str>;
+
+  /// Returns the grapheme at the position
+  fn get_grapheme(&self, index: usize) -> Option<&str>;
+
+  /// Returns the count of the graphemes
+  fn get_graphemes_count(&self) -> usize;
+
+  /// Returns the byte index of the grapheme at the position
+  ///
+  /// Returns the length of the text for the position past the end.
+  fn get_byte_index(&self, index: usize) -> usize;
+
+  /// Returns the position where the last word starts
+  ///
+  /// Trailing whitespaces belong to the last word.
+  fn get_last_word_start(&self) -> usize;
+
+  /// Returns whether the char completes the last grapheme
+  /// instead of starting a new one.
+  ///
+  /// I.e. a combining accent sent separately after a dead key or compose sequence.
+  fn is_extended_by(&self, c: char) -> bool;
+}
+
+impl GraphemeText for str {
+  fn get_graphemes(&self) -> Vec<&str> {
+    self.graphemes(true).collect()
+  }
+
+  fn get_grapheme(&self, index: usize) -> Option<&str> {
+    self.graphemes(true).nth(index)
+  }
+
+  fn get_graphemes_count(&self) -> usize {
+    self.graphemes(true).count()
+  }
+
+  fn get_byte_index(&self, index: usize) -> usize {
+    self
+      .grapheme_indices(true)
+      .nth(index)
+      .map_or(self.len(), |(byte_index, _)| byte_index)
+  }
+
+  fn get_last_word_start(&self) -> usize {
+    let graphemes = self.get_graphemes();
+
+    let is_whitespace = |grapheme: &&str| grapheme.trim().is_empty();
+
+    let word_end = graphemes.len()
+      - graphemes
+        .iter()
+        .rev()
+        .take_while(|grapheme| is_whitespace(grapheme))
+        .count();
+
+    graphemes[..word_end]
+      .iter()
+      .rposition(is_whitespace)
+      .map_or(0, |whitespace_index| whitespace_index + 1)
+  }
+
+  fn is_extended_by(&self, c: char) -> bool {
+    let Some(last_grapheme) = self.graphemes(true).next_back() else {
+      return false;
+    };
+
+    let mut extended_grapheme = last_grapheme.to_string();
+    extended_grapheme.push(c);
+
+    extended_grapheme.graphemes(true).count() == 1
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn count_accented_graphemes() {
+    let text = "zażółć e\u{301}té";
+
+    assert_eq!(text.get_graphemes_count(), 10);
+    assert_eq!(text.get_grapheme(7), Some("e\u{301}"));
+    assert_eq!(text.get_grapheme(8), Some("t"));
+    assert_eq!(text.get_byte_index(8), text.find('t').unwrap());
+    assert_eq!(text.get_byte_index(10), text.len());
+  }
+
+  #[test]
+  fn find_last_word_start() {
+    assert_eq!("żółw łódź  ".get_last_word_start(), 5);
+    assert_eq!("żółw".get_last_word_start(), 0);
+    assert_eq!("   ".get_last_word_start(), 0);
+    assert_eq!("".get_last_word_start(), 0);
+  }
+
+  #[test]
+  fn extend_by_combining_mark() {
+    assert!("cafe".is_extended_by('\u{301}'));
+    assert!(!"cafe".is_extended_by('s'));
+    assert!(!"".is_extended_by('\u{301}'));
+  }
+}
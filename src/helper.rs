@@ -1,7 +1,27 @@
 use std::iter::repeat_n;
 
-use crate::config::TukaiConfig;
-use rand::{Rng, seq::SliceRandom};
+use crate::{
+  config::{Quote, TukaiConfig},
+  snippet::Snippet,
+  storage::{key_stats::KeyStats, storage_handler::StorageHandler},
+};
+use rand::{
+  Rng,
+  distributions::{Distribution, WeightedIndex},
+  seq::SliceRandom,
+};
+
+/// Probability of a word being replaced by a number
+const NUMBER_PROBABILITY: f64 = 0.1;
+
+/// Probability of a sentence ending after a word
+const SENTENCE_END_PROBABILITY: f64 = 0.12;
+
+/// Probability of a punctuation mark inside of a sentence
+const PUNCTUATION_PROBABILITY: f64 = 0.15;
+
+/// Largest generated number (exclusive)
+const MAX_NUMBER: usize = 1000;
 
 pub struct Generator;
 
@@ -18,6 +38,11 @@ impl Generator {
       .unwrap_or_default()
   }
 
+  /// Returns the count of the words generated for the selected test
+  fn get_words_count(config: &TukaiConfig) -> usize {
+    config.typing_mode.get_words_count(&config.typing_duration)
+  }
+
   /// Generates a random string composed of words from a language-specific word list.
   ///
   /// This method reads words from a `words/{language}.txt` file based on the language
@@ -29,15 +54,102 @@ impl Generator {
   ///
   /// # Returns
   /// A `String` composed of randomly selected words.
+  ///
+  /// The words are selected with replacement,
+  /// so the text is not limited by the size of the word list.
   pub fn generate_random_string(config: &TukaiConfig) -> String {
     let mut rng = rand::thread_rng();
 
-    Generator::get_words(config)
-      .choose_multiple(&mut rng, config.typing_duration.as_seconds() * 2)
-      .fold(String::new(), |mut acc, c| {
-        acc.push_str(format!("{c} ").as_str());
-        acc
-      })
+    let words = Generator::get_words(config);
+    let chosen_words = (0..Generator::get_words_count(config))
+      .filter_map(|_| words.choose(&mut rng))
+      .collect::<Vec<&String>>();
+
+    Generator::join_words(config, chosen_words, &mut rng)
+  }
+
+  /// Joins the words into the text, each word is followed by a space.
+  ///
+  /// Applies the text options of the [`TukaiConfig`]:
+  /// - numbers replace some of the words
+  /// - sentences start with a capital letter
+  /// - sentences end with `.`, `!` or `?`, other words may get a comma, quotes or brackets
+  ///
+  /// Sentences are ended by a chance, so the capitalization works without the punctuation too.
+  /// The count of the words is kept.
+  fn join_words<R: Rng>(config: &TukaiConfig, words: Vec<&String>, rng: &mut R) -> String {
+    let mut text = String::new();
+    let mut is_sentence_start = true;
+
+    for word in words {
+      let mut word = if config.has_numbers && rng.gen_bool(NUMBER_PROBABILITY) {
+        rng.gen_range(0..MAX_NUMBER).to_string()
+      } else {
+        word.clone()
+      };
+
+      if config.has_capitalization && is_sentence_start {
+        let mut chars = word.chars();
+
+        if let Some(first_char) = chars.next() {
+          word = first_char.to_uppercase().chain(chars).collect();
+        }
+      }
+
+      is_sentence_start = rng.gen_bool(SENTENCE_END_PROBABILITY);
+
+      if config.has_punctuation {
+        if is_sentence_start {
+          word.push(*['.', '.', '!', '?'].choose(rng).unwrap_or(&'.'));
+        } else if rng.gen_bool(PUNCTUATION_PROBABILITY) {
+          word = match rng.gen_range(0..4) {
+            0 => format!("\"{word}\""),
+            1 => format!("({word})"),
+            _ => format!("{word},"),
+          };
+        }
+      }
+
+      text.push_str(&word);
+      text.push(' ');
+    }
+
+    text
+  }
+
+  /// Generates a string of words weighted towards the weak keys and bigrams.
+  ///
+  /// Words containing the keys and bigrams the user often mistakes or types slowly
+  /// (see [`KeyStats::get_word_weight`]) are more likely to be selected.
+  /// Falls back to [`Generator::generate_random_string`] without any typing data.
+  ///
+  /// # Parameters
+  /// - `config`: A reference to a [`TukaiConfig`] instance containing configuration options,
+  ///   including the language to use.
+  /// - `key_stats`: Typing data of the keys to weight the words by.
+  ///
+  /// # Returns
+  /// A `String` composed of randomly selected words.
+  pub fn generate_adaptive_string(config: &TukaiConfig, key_stats: &KeyStats) -> String {
+    if key_stats.is_empty() {
+      return Generator::generate_random_string(config);
+    }
+
+    let mut rng = rand::thread_rng();
+
+    let words = Generator::get_words(config);
+
+    let Ok(distribution) =
+      WeightedIndex::new(words.iter().map(|word| key_stats.get_word_weight(word)))
+    else {
+      return Generator::generate_random_string(config);
+    };
+
+    let chosen_words = (0..Generator::get_words_count(config))
+      .map(|_| &words[distribution.sample(&mut rng)])
+      .collect::<Vec<&String>>();
+
+    Generator::join_words(config, chosen_words, &mut rng)
   }
 
   /// Generates a repeated word string based on the provided configuration.
@@ -61,6 +173,40 @@ impl Generator {
     repeat_n(word, 50).collect::<Vec<String>>().join(" ")
   }
 
+  /// Picks a random quote of the current language.
+  ///
+  /// Returns `None` if the language has no quotes.
+  pub fn generate_quote(config: &TukaiConfig) -> Option<Quote> {
+    let mut rng = rand::thread_rng();
+
+    config
+      .get_language()
+      .load_quotes()
+      .ok()?
+      .choose(&mut rng)
+      .cloned()
+  }
+
+  /// Picks a random code snippet of the selected programming language
+  /// (or any language, if not selected).
+  ///
+  /// Returns `None` if there is no snippet of the language.
+  pub fn generate_snippet(config: &TukaiConfig) -> Option<Snippet> {
+    let mut rng = rand::thread_rng();
+
+    Snippet::load_snippets(&StorageHandler::get_local_dir_path().join("snippets"))
+      .into_iter()
+      .filter(|snippet| {
+        config
+          .code_lang
+          .as_ref()
+          .is_none_or(|code_lang| snippet.get_lang() == code_lang)
+      })
+      .collect::<Vec<Snippet>>()
+      .choose(&mut rng)
+      .cloned()
+  }
+
   /// Generates and returns a random motto string.
   ///
   /// This could be used, for example, in a screen footer.
@@ -82,3 +228,69 @@ impl Generator {
     String::from(mottos[random_index])
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::config::TypingMode;
+  use crate::storage::keystroke::{KeystrokeKind, KeystrokeLog};
+  use rand::{SeedableRng, rngs::StdRng};
+  use std::time::Duration;
+
+  #[test]
+  fn join_words_with_text_options() {
+    let words = vec![String::from("tukai"); 200];
+    let mut rng = StdRng::seed_from_u64(7);
+    let mut config = TukaiConfig::default();
+
+    let text = Generator::join_words(&config, words.iter().collect(), &mut rng);
+    assert_eq!(text, "tukai ".repeat(200));
+
+    config.toggle_punctuation();
+    config.toggle_capitalization();
+    config.toggle_numbers();
+
+    let text = Generator::join_words(&config, words.iter().collect(), &mut rng);
+
+    assert_eq!(text.split_whitespace().count(), 200);
+    assert!(text.starts_with("Tukai") || text.starts_with(|c: char| c.is_ascii_digit()));
+    assert!(text.chars().any(|c| c.is_ascii_digit()));
+    assert!(text.contains(['.', '!', '?']));
+    assert!(text.contains(", ") && text.contains('(') && text.contains('"'));
+
+    // Sentences start with a capital letter
+    assert!(
+      text
+        .split(['.', '!', '?'])
+        .skip(1)
+        .filter_map(|sentence| sentence.trim_start().chars().next())
+        .all(|c| !c.is_lowercase())
+    );
+  }
+
+  #[test]
+  fn adapt_words_count_to_typing_mode() {
+    let mut keystroke_log = KeystrokeLog::new();
+    keystroke_log.push(Duration::ZERO, KeystrokeKind::Incorrect, 'x', Some('a'));
+
+    let mut key_stats = KeyStats::default();
+    key_stats.add_keystroke_log(&keystroke_log);
+
+    let mut config = TukaiConfig::default();
+    config.typing_mode = TypingMode::Words(10);
+
+    let text = Generator::generate_adaptive_string(&config, &key_stats);
+    assert_eq!(text.split_whitespace().count(), 10);
+  }
+
+  #[test]
+  fn generate_more_words_than_the_language_has() {
+    let mut config = TukaiConfig::default();
+    config.typing_mode = TypingMode::Words(2000);
+
+    assert!(Generator::get_words(&config).len() < 2000);
+
+    let text = Generator::generate_random_string(&config);
+    assert_eq!(text.split_whitespace().count(), 2000);
+  }
+}
@@ -1,27 +1,41 @@
 mod app;
+mod cli;
 mod config;
 mod file_handler;
 
 mod event_handler;
+mod graphemes;
 mod helper;
 mod screens;
+mod snippet;
 mod storage;
 
+use std::time::Duration;
+
 use anyhow::Result;
 use app::Tukai;
-use config::TukaiConfigBuilder;
+use clap::Parser;
+use cli::TukaiCli;
 use event_handler::EventHandler;
 
 #[tokio::main]
 async fn main() -> Result<()> {
-  let mut terminal = ratatui::init();
-  let mut event_handler = EventHandler::new();
+  let mut cli = TukaiCli::parse();
+  let command = cli.command.take();
+  let tick_rate = Duration::from_millis(cli.tick_rate);
+
+  let app_config_builder = cli.into_config_builder();
 
-  let app_config = TukaiConfigBuilder::new().build();
+  if let Some(command) = command {
+    return command.execute(app_config_builder.get_file_path());
+  }
+
+  let mut terminal = ratatui::init();
+  let mut event_handler = EventHandler::new(tick_rate);
 
   terminal.clear()?;
 
-  let app_result = Tukai::try_new(&mut event_handler, app_config)?
+  let app_result = Tukai::try_new(&mut event_handler, app_config_builder)?
     .run(&mut terminal)
     .await;
 
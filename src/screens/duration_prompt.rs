@@ -0,0 +1,145 @@
+use ratatui::{
+  Frame,
+  crossterm::event::{KeyCode, KeyEvent},
+  layout::{Alignment, Constraint, Flex, Layout},
+  style::{Style, Stylize},
+  text::{Line, Span, Text},
+  widgets::{Block, BorderType, Clear, Paragraph},
+};
+
+use crate::{
+  config::{TukaiConfig, TypingDuration},
+  screens::ToDark,
+};
+
+/// Longest accepted input (count of digits)
+const MAX_INPUT_LEN: usize = 5;
+
+/// Result of a key handled by the prompt
+pub enum DurationPromptEvent {
+  // Key was consumed, the prompt stays open
+  Consumed,
+
+  // Prompt was confirmed with the valid duration
+  Confirmed(TypingDuration),
+
+  // Prompt was closed without a change
+  Cancelled,
+}
+
+/// Popup prompting for a custom typing duration (seconds)
+#[derive(Default)]
+pub struct DurationPrompt {
+  // Typed digits
+  input: String,
+}
+
+impl DurationPrompt {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Returns the typed duration
+  ///
+  /// None if the input is empty or zero.
+  pub fn get_typing_duration(&self) -> Option<TypingDuration> {
+    self
+      .input
+      .parse::<usize>()
+      .ok()
+      .and_then(TypingDuration::from_seconds)
+  }
+
+  /// Handles key events
+  ///
+  /// Accepts only digits, an invalid duration cannot be confirmed.
+  pub fn handle_events(&mut self, key_event: KeyEvent) -> DurationPromptEvent {
+    match key_event.code {
+      KeyCode::Char(c) if c.is_ascii_digit() && self.input.len() < MAX_INPUT_LEN => {
+        self.input.push(c);
+      }
+      KeyCode::Backspace => {
+        self.input.pop();
+      }
+      KeyCode::Enter => {
+        if let Some(typing_duration) = self.get_typing_duration() {
+          return DurationPromptEvent::Confirmed(typing_duration);
+        }
+      }
+      KeyCode::Esc => return DurationPromptEvent::Cancelled,
+      _ => {}
+    }
+
+    DurationPromptEvent::Consumed
+  }
+
+  /// Renders the prompt popup in the center of the frame
+  pub fn render(&self, frame: &mut Frame, config: &TukaiConfig) {
+    let app_layout = config.get_layout();
+    let primary_color = app_layout.get_primary_color();
+
+    let block = Block::bordered()
+      .title(" Custom duration ")
+      .title_alignment(Alignment::Center)
+      .style(config.get_bg_color())
+      .border_type(BorderType::Rounded)
+      .border_style(Style::new().fg(primary_color));
+
+    let text = Text::from(vec![
+      Line::from(vec![
+        Span::from("⏳ Seconds: "),
+        Span::from(format!("{}▏", self.input)).bold(),
+      ])
+      .style(Style::default().fg(primary_color)),
+      Line::from(""),
+      Line::from(vec![
+        Span::from("Confirm").style(Style::default().fg(primary_color.to_dark())),
+        Span::from(" enter |").style(Style::default().fg(primary_color).bold()),
+        Span::from(" Cancel").style(Style::default().fg(primary_color.to_dark())),
+        Span::from(" esc").style(Style::default().fg(primary_color).bold()),
+      ]),
+    ]);
+
+    let p = Paragraph::new(text).block(block).centered();
+
+    let vertical = Layout::vertical([Constraint::Length(5)]).flex(Flex::Center);
+    let horizontal = Layout::horizontal([Constraint::Length(32)]).flex(Flex::Center);
+    let [area] = vertical.areas(frame.area());
+    let [area] = horizontal.areas(area);
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(p, area);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use ratatui::crossterm::event::KeyModifiers;
+
+  fn type_keys(prompt: &mut DurationPrompt, keys: &str) {
+    for c in keys.chars() {
+      prompt.handle_events(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+    }
+  }
+
+  #[test]
+  fn prompt_custom_duration() {
+    let mut prompt = DurationPrompt::new();
+    type_keys(&mut prompt, "9x0");
+
+    assert_eq!(
+      prompt.get_typing_duration(),
+      Some(TypingDuration::Custom(90))
+    );
+
+    type_keys(&mut prompt, "0000");
+    assert_eq!(prompt.input, "90000");
+
+    prompt.input = String::from("00");
+    assert!(matches!(
+      prompt.handle_events(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE)),
+      DurationPromptEvent::Consumed
+    ));
+  }
+}
@@ -1,11 +1,16 @@
 pub mod repeat;
+pub mod replay;
 pub mod stats;
 pub mod typing;
 pub mod practice;
+pub mod duration_prompt;
+pub mod typing_input;
 
 use std::cell::RefCell;
 use std::rc::Rc;
 
+use serde::{Deserialize, Serialize};
+
 use ratatui::{
   Frame,
   crossterm::event::KeyEvent,
@@ -20,12 +25,15 @@ use crate::{
   storage::storage_handler::StorageHandler,
 };
 
-#[derive(PartialEq, Hash, Eq, Debug)]
+#[derive(
+  PartialEq, Hash, Eq, Debug, Clone, Copy, Default, clap::ValueEnum, Serialize, Deserialize,
+)]
 pub enum ActiveScreenEnum {
+  #[default]
   Typing,
   Repeat,
   Practice,
-  Stats
+  Stats,
 }
 
 #[allow(unused)]
@@ -133,7 +141,6 @@ impl<'a> InstructionWidget<'a> {
 
 pub trait Screen {
   // fn new(config: Rc<RefCell<TukaiConfig>>) -> Box<Screen>;
-  fn increment_time_secs(&mut self);
   fn get_config(&self) -> &Rc<RefCell<TukaiConfig>>;
   fn get_screen_name(&self) -> String;
   fn get_remaining_time(&self) -> usize;
@@ -150,20 +157,31 @@ pub trait Screen {
 
   /// Returns the application title
   /// including version from the `Cargo.toml`.
+  ///
+  /// The enabled options of the generated text are shown after the language.
   fn get_title<'a>(&self) -> Title<'a> {
     let app_config = self.get_config().borrow();
     let app_layout = app_config.get_layout();
 
+    let text_options = app_config
+      .get_text_options_pretty()
+      .map(|text_options| format!(" 》{text_options}"))
+      .unwrap_or_default();
+
     Title::from(format!(
-      " tukai v{} 》{} 》{} 》{} ",
+      " tukai v{} 》{} 》{}{} 》{} ",
       env!("CARGO_PKG_VERSION"),
       app_layout.get_active_layout_name(),
       app_config.get_language().get_lang_code(),
+      text_options,
       self.get_screen_name()
     ))
   }
 
-  fn reset(&mut self);
+  /// Resets the screen for the next run
+  ///
+  /// The stored runs are read from the app's storage handler.
+  fn reset(&mut self, storage_handler: &StorageHandler);
 
   /// Handles key events
   ///
@@ -175,6 +193,23 @@ pub trait Screen {
     false
   }
 
+  /// Returns whether the run is over and should be stopped
+  ///
+  /// Default set to the time is over.
+  fn is_finished(&self) -> bool {
+    self.get_remaining_time() == 0
+  }
+
+  /// Returns whether the screen renders an animation.
+  ///
+  /// Animated screens are redrawn on every frame event.
+  fn is_animated(&self) -> bool {
+    false
+  }
+
+  /// Advances the animation of the screen
+  fn animate(&mut self) {}
+
   /// Returns whether the popup is visible.
   ///
   /// Default set to false (not used in stats screen)
@@ -1,105 +1,74 @@
-use std::{cell::RefCell, collections::HashSet, rc::Rc};
+use std::{
+  cell::RefCell,
+  rc::Rc,
+  time::{Duration, Instant},
+};
 
 use ratatui::{
   Frame,
   crossterm::event::{KeyCode, KeyEvent},
   layout::{Alignment, Rect},
-  style::{Modifier, Style},
+  style::Style,
   text::{Line, Span, Text},
   widgets::{Block, BorderType, Borders, Padding, Paragraph, Wrap},
 };
 
 use crate::{
   config::{TukaiConfig, TukaiLayout, TukaiLayoutColorTypeEnum},
+  graphemes::GraphemeText,
   helper::Generator,
-  screens::{Instruction, InstructionWidget, Screen},
+  screens::{Instruction, InstructionWidget, Screen, typing_input::TypingInput},
+  storage::{key_stats::KeyStats, storage_handler::StorageHandler},
 };
 
 use super::ActiveScreenEnum;
 
-/// Handler for incorrect symbols
-///
-/// Inserts incorrect characters into a HashSet
-pub struct MistakeHandler {
-  mistakes_indexes: HashSet<usize>,
-}
-
-impl MistakeHandler {
-  /// Creates MistakeHandler with empty HashSet
-  fn new() -> Self {
-    Self {
-      mistakes_indexes: HashSet::new(),
-    }
-  }
-
-  /// Verifies if the character is mistaken
-  pub fn is_char_mistaken(&self, char_index: usize) -> bool {
-    self.mistakes_indexes.contains(&char_index)
-  }
-
-  /// Adds the typed character into the mistakes
-  pub fn add_to_mistakes_indexes(&mut self, char_index: usize) -> bool {
-    self.mistakes_indexes.insert(char_index)
-  }
-
-  /// Removes the typed character from mistakes
-  pub fn remove_from_mistakes_indexes(&mut self, char_index: usize) -> bool {
-    self.mistakes_indexes.remove(&char_index)
-  }
-}
-
 pub struct PracticeScreen {
   /// Application config
   config: Rc<RefCell<TukaiConfig>>,
 
-  /// Random generated text from a words list
-  pub generated_text: String,
-
-  /// User typed input
-  pub input: String,
-
-  /// Handle incorrect characters
-  pub mistake_handler: MistakeHandler,
+  /// Text to type and the user typed input
+  typing_input: TypingInput,
 
   // Typing running
   is_running: bool,
 
-  /// The current cursor index withing generated_text
-  cursor_index: usize,
-
   /// Block motto
   motto: String,
+
+  // Typing data of the previous runs and the finished texts of the session
+  key_stats: KeyStats,
+
+  // Start of the typing of the current text
+  text_started_at: Option<Instant>,
 }
 
 impl PracticeScreen {
-  pub fn new(config: Rc<RefCell<TukaiConfig>>) -> Self {
-    let generated_text = Generator::generate_random_string(&config.borrow());
+  pub fn new(config: Rc<RefCell<TukaiConfig>>, storage_handler: &StorageHandler) -> Self {
+    let key_stats = KeyStats::from_stats(storage_handler.get_data_stats());
+    let generated_text = Generator::generate_adaptive_string(&config.borrow(), &key_stats);
 
     Self {
       config,
 
-      generated_text,
-
-      input: String::new(),
-
-      mistake_handler: MistakeHandler::new(),
+      typing_input: TypingInput::new(generated_text),
 
       is_running: false,
 
-      cursor_index: 0,
-
       motto: Generator::generate_random_motto(),
+
+      key_stats,
+
+      text_started_at: None,
     }
   }
 }
 
 impl Screen for PracticeScreen {
   fn is_running(&self) -> bool {
-      true
+    true
   }
 
-  fn increment_time_secs(&mut self) {}
-
   fn get_config(&self) -> &Rc<RefCell<TukaiConfig>> {
     &self.config
   }
@@ -131,26 +100,32 @@ impl Screen for PracticeScreen {
   }
 
   /// Resets all necessary properties
-  fn reset(&mut self) {
-    self.mistake_handler = MistakeHandler::new();
-    self.cursor_index = 0;
-    self.input = String::new();
-    let app_config = self.config.borrow();
-    self.generated_text = Generator::generate_random_string(&app_config);
+  ///
+  /// The keystrokes typed so far still adapt the new text.
+  fn reset(&mut self, _storage_handler: &StorageHandler) {
+    self.next_text();
   }
 
   fn handle_events(&mut self, key_event: KeyEvent) -> bool {
-    if self.cursor_index > 0 && !self.is_running() {
+    if self.typing_input.get_cursor_index() > 0 && !self.is_running() {
       return false;
     }
 
     match key_event.code {
       KeyCode::Char(c) => {
-        if self.cursor_index == 0 {
+        // The offsets of the text keystrokes are counted from its first keystroke
+        if self.text_started_at.is_none() {
           self.run();
         }
 
         self.move_cursor_forward_with(c);
+
+        if self.typing_input.get_cursor_index()
+          >= self.typing_input.get_text().get_graphemes_count()
+        {
+          self.next_text();
+        }
+
         true
       }
       KeyCode::Backspace => {
@@ -256,81 +231,53 @@ impl PracticeScreen {
   // Starts the running typing process
   fn run(&mut self) {
     self.is_running = true;
+    self.text_started_at = Some(Instant::now());
   }
 
-  /// Validates an inserted char
-  ///
-  /// If it is not valid, insert it into the set of mistakes
-  fn validate_input_char(&mut self, inserted_char: char) {
-    if let Some(generated_char) = self.generated_text.chars().nth(self.cursor_index)
-      && generated_char != inserted_char
-    {
-      self
-        .mistake_handler
-        .add_to_mistakes_indexes(self.cursor_index);
-    }
+  /// Generates the next text adapted to the keystrokes typed so far
+  fn next_text(&mut self) {
+    self
+      .key_stats
+      .add_keystroke_log(&self.typing_input.take_keystroke_log());
+
+    self.text_started_at = None;
+
+    let app_config = self.config.borrow();
+    self.typing_input = TypingInput::new(Generator::generate_adaptive_string(
+      &app_config,
+      &self.key_stats,
+    ));
+  }
+
+  /// Returns the offset of a keystroke typed now
+  /// (time elapsed since the start of the text)
+  fn get_keystroke_offset(&self) -> Duration {
+    self
+      .text_started_at
+      .map(|text_started_at| text_started_at.elapsed())
+      .unwrap_or_default()
   }
 
   /// Moves the cursor position forward
   ///
-  /// Also validates a char
+  /// Also validates a grapheme.
   fn move_cursor_forward_with(&mut self, c: char) {
-    self.validate_input_char(c);
-    self.input.push(c);
-    self.cursor_index += 1;
+    let offset = self.get_keystroke_offset();
+    self.typing_input.type_char(c, offset);
   }
 
   /// Moves the cursor position backward
-  ///
-  /// Remove the incorrect symbol from the set if its exists
   fn move_cursor_backward(&mut self) {
-    if self.input.pop().is_none() {
-      return;
-    }
-
-    self.cursor_index -= 1;
-
-    if self.mistake_handler.is_char_mistaken(self.cursor_index) {
-      self
-        .mistake_handler
-        .remove_from_mistakes_indexes(self.cursor_index);
-    }
+    let offset = self.get_keystroke_offset();
+    self.typing_input.backspace(offset);
   }
 
-  // Deletes the last word form the input.
-  // Handles trailing spaces and updates mistakes.
+  /// Deletes the last word from the input.
+  ///
+  /// Trailing whitespaces are deleted with the word.
   pub fn delete_last_word(&mut self) {
-    if self.input.is_empty() {
-      return;
-    }
-
-    let original_input_len = self.input.len();
-
-    // Find the end of the actual contnet (trim trailing whitespace for logic)
-    let trimmed_end_len = self.input.trim_end().len();
-
-    if trimmed_end_len == 0 {
-      // Input was all spaces
-      for i in 0..original_input_len {
-        self.mistake_handler.remove_from_mistakes_indexes(i);
-      }
-      self.input.clear();
-      self.cursor_index = 0;
-      return;
-    }
-
-    // Find the last space before the last word in the trimmed part
-    let last_word_start_idx = match self.input[..trimmed_end_len].rfind(' ') {
-      Some(space_idx) => space_idx + 1, // Word starts after the space
-      _ => 0,                        // No space found, word starts at the beginning
-    };
-
-    for i in last_word_start_idx..original_input_len {
-      self.mistake_handler.remove_from_mistakes_indexes(i);
-    }
-
-    self.input.truncate(last_word_start_idx);
-    self.cursor_index = self.input.len();
+    let offset = self.get_keystroke_offset();
+    self.typing_input.delete_last_word(offset);
   }
 
   /// Prepares and returns a paragraph.
@@ -340,33 +287,25 @@ impl PracticeScreen {
     let error_color = layout.get_error_color();
     let text_color = layout.get_text_color();
 
-    let practice_line =
-      Line::from("🌱 Practice").style(Style::default().fg(primary_color));
+    let weakest = self.key_stats.get_weakest(5);
+
+    let practice_text = if weakest.is_empty() {
+      String::from("🌱 Practice")
+    } else {
+      format!("🌱 Practice · focus: {}", weakest.join(" "))
+    };
+
+    let practice_line = Line::from(practice_text).style(Style::default().fg(primary_color));
 
     let text_line = self
-      .generated_text
-      .chars()
-      .enumerate()
-      .map(|(i, c)| {
-        if i == self.cursor_index {
-          Span::from(c.to_string()).style(
-            Style::default()
-              .fg(layout.get_text_current_color())
-              .bg(layout.get_text_current_bg_color()),
-          )
-        } else if i < self.cursor_index {
-          if self.input.chars().nth(i) == Some(c) {
-            Span::from(c.to_string()).style(Style::default().fg(primary_color))
-          } else {
-            Span::from(c.to_string()).style(
-              Style::default()
-                .fg(error_color)
-                .add_modifier(Modifier::CROSSED_OUT),
-            )
-          }
-        } else {
-          Span::from(c.to_string()).style(Style::default().fg(text_color))
-        }
+      .typing_input
+      .get_styled_graphemes(layout, primary_color, error_color, |_| {
+        Style::default().fg(text_color)
+      })
+      .into_iter()
+      .map(|styled_grapheme| {
+        Span::from(styled_grapheme.get_compared().get_displayed().to_string())
+          .style(styled_grapheme.get_style())
       })
       .collect::<Line>();
 
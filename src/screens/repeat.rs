@@ -1,10 +1,10 @@
-use std::{cell::RefCell, collections::HashSet, rc::Rc};
+use std::{cell::RefCell, rc::Rc, time::Duration};
 
 use ratatui::{
   Frame,
   crossterm::event::{KeyCode, KeyEvent},
   layout::{Alignment, Rect},
-  style::{Modifier, Style},
+  style::Style,
   text::{Line, Span, Text},
   widgets::{Block, BorderType, Borders, Padding, Paragraph, Wrap},
 };
@@ -12,57 +12,18 @@ use ratatui::{
 use crate::{
   config::{TukaiConfig, TukaiLayout, TukaiLayoutColorTypeEnum},
   helper::Generator,
-  screens::{Instruction, InstructionWidget, Screen, ToDark},
+  screens::{Instruction, InstructionWidget, Screen, ToDark, typing_input::TypingInput},
+  storage::storage_handler::StorageHandler,
 };
 
 use super::ActiveScreenEnum;
 
-/// Handler for incorrect symbols
-///
-/// Inserts incorrect characters into a HashSet
-pub struct MistakeHandler {
-  mistakes_indexes: HashSet<usize>,
-}
-
-impl MistakeHandler {
-  /// Creates MistakeHandler with empty HashSet
-  fn new() -> Self {
-    Self {
-      mistakes_indexes: HashSet::new(),
-    }
-  }
-
-  /// Verifies if the character is mistaken
-  pub fn is_char_mistaken(&self, char_index: usize) -> bool {
-    self.mistakes_indexes.contains(&char_index)
-  }
-
-  /// Adds the typed character into the mistakes
-  pub fn add_to_mistakes_indexes(&mut self, char_index: usize) -> bool {
-    self.mistakes_indexes.insert(char_index)
-  }
-
-  /// Removes the typed character from mistakes
-  pub fn remove_from_mistakes_indexes(&mut self, char_index: usize) -> bool {
-    self.mistakes_indexes.remove(&char_index)
-  }
-}
-
 pub struct RepeatScreen {
   /// Application config
   config: Rc<RefCell<TukaiConfig>>,
 
-  /// Random generated text from a words list
-  pub generated_text: String,
-
-  /// User typed input
-  pub input: String,
-
-  /// Handle incorrect characters
-  pub mistake_handler: MistakeHandler,
-
-  /// The current cursor index withing generated_text
-  cursor_index: usize,
+  /// Text to type and the user typed input
+  typing_input: TypingInput,
 
   /// Block motto
   motto: String,
@@ -75,13 +36,7 @@ impl RepeatScreen {
     Self {
       config,
 
-      generated_text,
-
-      input: String::new(),
-
-      mistake_handler: MistakeHandler::new(),
-
-      cursor_index: 0,
+      typing_input: TypingInput::new(generated_text),
 
       motto: Generator::generate_random_motto(),
     }
@@ -93,8 +48,6 @@ impl Screen for RepeatScreen {
     true
   }
 
-  fn increment_time_secs(&mut self) {}
-
   fn get_config(&self) -> &Rc<RefCell<TukaiConfig>> {
     &self.config
   }
@@ -116,16 +69,13 @@ impl Screen for RepeatScreen {
   }
 
   /// Resets all necessary properties
-  fn reset(&mut self) {
-    self.mistake_handler = MistakeHandler::new();
-    self.cursor_index = 0;
-    self.input = String::new();
+  fn reset(&mut self, _storage_handler: &StorageHandler) {
     let app_config = self.config.borrow();
-    self.generated_text = Generator::generate_repeated_word(&app_config);
+    self.typing_input = TypingInput::new(Generator::generate_repeated_word(&app_config));
   }
 
   fn handle_events(&mut self, key_event: KeyEvent) -> bool {
-    if self.cursor_index > 0 && !self.is_running() {
+    if self.typing_input.get_cursor_index() > 0 && !self.is_running() {
       return false;
     }
 
@@ -237,80 +187,25 @@ impl Screen for RepeatScreen {
 }
 
 impl RepeatScreen {
-  /// Validates an inserted char
-  ///
-  /// If it is not valid, insert it into the set of mistakes
-  fn validate_input_char(&mut self, inserted_char: char) {
-    if let Some(generated_char) = self.generated_text.chars().nth(self.cursor_index)
-      && generated_char != inserted_char
-    {
-      self
-        .mistake_handler
-        .add_to_mistakes_indexes(self.cursor_index);
-    }
-  }
-
   /// Moves the cursor position forward
   ///
-  /// Also validates a char
+  /// Also validates a grapheme.
+  /// The keystrokes of the repeated word are not timed.
   fn move_cursor_forward_with(&mut self, c: char) {
-    self.validate_input_char(c);
-    self.input.push(c);
-    self.cursor_index += 1;
+    self.typing_input.type_char(c, Duration::ZERO);
   }
 
   /// Moves the cursor position backward
-  ///
-  /// Remove the incorrect symbol from the set if its exists
   fn move_cursor_backward(&mut self) {
-    if self.input.pop().is_none() {
-      return;
-    }
-
-    self.cursor_index -= 1;
-
-    if self.mistake_handler.is_char_mistaken(self.cursor_index) {
-      self
-        .mistake_handler
-        .remove_from_mistakes_indexes(self.cursor_index);
-    }
+    self.typing_input.backspace(Duration::ZERO);
   }
 
-  // Deletes the last word form the input.
-  // Handles trailing spaces and updates mistakes.
+  /// Deletes the last word from the input.
+  ///
+  /// Trailing whitespaces are deleted with the word.
   #[allow(unused)]
   pub fn delete_last_word(&mut self) {
-    if self.input.is_empty() {
-      return;
-    }
-
-    let original_input_len = self.input.len();
-
-    // Find the end of the actual contnet (trim trailing whitespace for logic)
-    let trimmed_end_len = self.input.trim_end().len();
-
-    if trimmed_end_len == 0 {
-      // Input was all spaces
-      for i in 0..original_input_len {
-        self.mistake_handler.remove_from_mistakes_indexes(i);
-      }
-      self.input.clear();
-      self.cursor_index = 0;
-      return;
-    }
-
-    // Find the last space before the last word in the trimmed part
-    let last_word_start_idx = match self.input[..trimmed_end_len].rfind(' ') {
-      Some(space_idx) => space_idx + 1, // Word starts after the space
-      none => 0,                        // No space found, word starts at the beginning
-    };
-
-    for i in last_word_start_idx..original_input_len {
-      self.mistake_handler.remove_from_mistakes_indexes(i);
-    }
-
-    self.input.truncate(last_word_start_idx);
-    self.cursor_index = self.input.len();
+    self.typing_input.delete_last_word(Duration::ZERO);
   }
 
   /// Prepares and returns a paragraph.
@@ -339,29 +234,14 @@ impl RepeatScreen {
       Line::from("🔄 Repeat word").style(Style::default().fg(layout.get_primary_color()));
 
     let text_line = self
-      .generated_text
-      .chars()
-      .enumerate()
-      .map(|(i, c)| {
-        if i == self.cursor_index {
-          Span::from(c.to_string()).style(
-            Style::default()
-              .fg(layout.get_text_current_color())
-              .bg(layout.get_text_current_bg_color()),
-          )
-        } else if i < self.cursor_index {
-          if self.input.chars().nth(i) == Some(c) {
-            Span::from(c.to_string()).style(Style::default().fg(primary_color))
-          } else {
-            Span::from(c.to_string()).style(
-              Style::default()
-                .fg(error_color)
-                .add_modifier(Modifier::CROSSED_OUT),
-            )
-          }
-        } else {
-          Span::from(c.to_string()).style(Style::default().fg(text_color))
-        }
+      .typing_input
+      .get_styled_graphemes(layout, primary_color, error_color, |_| {
+        Style::default().fg(text_color)
+      })
+      .into_iter()
+      .map(|styled_grapheme| {
+        Span::from(styled_grapheme.get_compared().get_displayed().to_string())
+          .style(styled_grapheme.get_style())
       })
       .collect::<Line>();
 
@@ -0,0 +1,78 @@
+use std::{cell::RefCell, rc::Rc, time::Instant};
+
+use ratatui::{Frame, layout::Rect};
+
+use crate::{
+  config::TukaiConfig,
+  screens::{Screen, typing::TypingScreen},
+  storage::stats::Stat,
+};
+
+/// Replay of a recorded run
+///
+/// Applies the recorded keystrokes onto the `TypingScreen`
+/// with the original timing, including mistakes and corrections.
+pub struct Replay {
+  config: Rc<RefCell<TukaiConfig>>,
+
+  // Replayed run
+  stat: Stat,
+
+  // Screen rendering the replayed run
+  typing_screen: TypingScreen,
+
+  // Count of the already applied keystrokes
+  applied_count: usize,
+
+  // Start of the replay
+  started_at: Instant,
+}
+
+impl Replay {
+  /// Creates a replay of the run
+  ///
+  /// Returns None if the run has no recorded keystrokes (or text).
+  pub fn new(config: Rc<RefCell<TukaiConfig>>, stat: Stat) -> Option<Self> {
+    if stat.get_keystroke_log().is_empty() || stat.get_text().is_empty() {
+      return None;
+    }
+
+    Some(Self {
+      typing_screen: TypingScreen::new_replay(Rc::clone(&config), &stat),
+      config,
+      stat,
+      applied_count: 0,
+      started_at: Instant::now(),
+    })
+  }
+
+  /// Starts the replay from the beginning
+  pub fn restart(&mut self) {
+    self.typing_screen = TypingScreen::new_replay(Rc::clone(&self.config), &self.stat);
+    self.applied_count = 0;
+    self.started_at = Instant::now();
+  }
+
+  /// Applies the keystrokes recorded until the elapsed time
+  pub fn animate(&mut self) {
+    let elapsed = self.started_at.elapsed();
+
+    let keystrokes = self.stat.get_keystroke_log().get_keystrokes_until(elapsed);
+
+    for keystroke in keystrokes[self.applied_count..].iter() {
+      self.typing_screen.apply_keystroke(keystroke);
+    }
+
+    self.applied_count = keystrokes.len();
+  }
+
+  /// Returns whether all keystrokes were replayed and the run time is over
+  pub fn is_finished(&self) -> bool {
+    self.applied_count == self.stat.get_keystroke_log().len()
+      && self.started_at.elapsed() >= self.stat.get_elapsed()
+  }
+
+  pub fn render(&self, frame: &mut Frame, area: Rect) {
+    self.typing_screen.render(frame, area);
+  }
+}
@@ -1,42 +1,155 @@
-use std::{cell::RefCell, rc::Rc};
+use std::{cell::RefCell, path::PathBuf, rc::Rc};
 
 use crate::{
   config::{TukaiConfig, TukaiLayoutColorTypeEnum},
-  screens::{Instruction, InstructionWidget, Screen, ToDark},
+  screens::{Instruction, InstructionWidget, Screen, ToDark, replay::Replay},
   storage::{
+    key_stats::KeyStats,
+    stat_export::{ExportFormat, StatExport},
+    stat_filter::StatFilter,
     stats::Stat,
     storage_handler::{StatOverview, StorageHandler},
   },
 };
 
+use anyhow::{Result, anyhow};
 use ratatui::{
   Frame,
-  crossterm::event::KeyEvent,
+  crossterm::event::{KeyCode, KeyEvent},
   layout::{Alignment, Constraint, Direction, Layout, Rect},
-  style::{Style, Stylize},
+  style::{Color, Modifier, Style, Stylize},
   symbols,
   text::{Line, Span},
   widgets::{
     Axis, Block, BorderType, Borders, Cell, Chart, Dataset, GraphType, Padding, Paragraph, Row,
-    Table,
+    Table, TableState,
   },
 };
 
 use super::ActiveScreenEnum;
 
+/// Count of the runs skipped by the page up/down
+const PAGE_ROWS_COUNT: usize = 10;
+
+/// Keys displayed in the heatmap, row by row
+const HEATMAP_KEYBOARD_ROWS: [&str; 4] = ["1234567890", "qwertyuiop", "asdfghjkl", "zxcvbnm"];
+
+/// Value displayed in the key heatmap
+#[derive(Clone, Copy, PartialEq)]
+enum HeatmapMode {
+  // Error rate per key
+  Errors,
+
+  // Average delay before the key was typed
+  Latency,
+}
+
+impl HeatmapMode {
+  fn switch(self) -> Self {
+    match self {
+      HeatmapMode::Errors => HeatmapMode::Latency,
+      HeatmapMode::Latency => HeatmapMode::Errors,
+    }
+  }
+}
+
 pub struct StatsScreen {
   config: Rc<RefCell<TukaiConfig>>,
+
+  // Result of the last action (displayed under the last runs table)
+  status_message: Option<String>,
+
+  // Index of the selected run in the last runs table
+  selected_index: usize,
+
+  // Replay of the selected run
+  replay: Option<Replay>,
+
+  // Value displayed in the key heatmap
+  heatmap_mode: HeatmapMode,
+
+  // Run metrics columns are displayed in the last runs table
+  is_metrics_visible: bool,
+
+  // Filter and sort order of the displayed runs
+  stat_filter: StatFilter,
+
+  // Storage loaded when the screen was opened (None if it failed to load)
+  storage_handler: Option<StorageHandler>,
+
+  // Data of the displayed runs, updated with the filter
+  stats_data: StatsData,
+}
+
+/// Data of the runs displayed by the widgets
+///
+/// Calculated from the filtered runs,
+/// so it is not recalculated on every render.
+#[derive(Default)]
+struct StatsData {
+  // Filtered runs in the sort order of the filter
+  sorted_stats: Vec<Stat>,
+
+  // Filtered runs sorted by average WPM
+  best_stats: Vec<Stat>,
+
+  // Best WPM and the WPM progress of the filtered runs
+  chart_data: (usize, Vec<(f64, f64)>),
+
+  // Overview of the filtered runs
+  stat_overview: StatOverview,
+
+  // Typing data of the filtered runs
+  key_stats: KeyStats,
+}
+
+impl StatsData {
+  fn new(storage_handler: &StorageHandler, stat_filter: &StatFilter) -> Self {
+    let sorted_stats = storage_handler.get_data_stats_sorted(stat_filter);
+
+    Self {
+      key_stats: KeyStats::from_stats(&sorted_stats),
+      best_stats: storage_handler.get_data_stats_best(stat_filter),
+      chart_data: storage_handler.get_data_for_chart(stat_filter),
+      stat_overview: storage_handler.get_data_for_overview(stat_filter),
+      sorted_stats,
+    }
+  }
 }
 
 impl StatsScreen {
+  /// Creates the screen of the stored runs
+  ///
+  /// The storage is loaded once, an error is displayed as the status message.
   pub fn new(config: Rc<RefCell<TukaiConfig>>) -> Self {
-    Self { config }
+    let stat_filter = StatFilter::new();
+
+    let (storage_handler, status_message) =
+      match StorageHandler::new(config.borrow().get_file_path()).init() {
+        Ok(storage_handler) => (Some(storage_handler), None),
+        Err(error) => (None, Some(format!(" Failed to load stats: {error} "))),
+      };
+
+    let stats_data = storage_handler
+      .as_ref()
+      .map(|storage_handler| StatsData::new(storage_handler, &stat_filter))
+      .unwrap_or_default();
+
+    Self {
+      config,
+      status_message,
+      selected_index: 0,
+      replay: None,
+      heatmap_mode: HeatmapMode::Errors,
+      is_metrics_visible: false,
+      stat_filter,
+      storage_handler,
+      stats_data,
+    }
   }
 }
 
 impl Screen for StatsScreen {
-  fn increment_time_secs(&mut self) {}
-
   fn get_config(&self) -> &Rc<RefCell<TukaiConfig>> {
     &self.config
   }
@@ -53,11 +166,87 @@ impl Screen for StatsScreen {
     Some(ActiveScreenEnum::Practice)
   }
 
-  fn reset(&mut self) {}
+  /// Restarts the replay
+  fn reset(&mut self, _storage_handler: &StorageHandler) {
+    if let Some(replay) = self.replay.as_mut() {
+      replay.restart();
+    }
+  }
+
+  fn is_animated(&self) -> bool {
+    self
+      .replay
+      .as_ref()
+      .is_some_and(|replay| !replay.is_finished())
+  }
 
-  #[allow(dead_code)]
-  fn handle_events(&mut self, _key: KeyEvent) -> bool {
-    false
+  fn animate(&mut self) {
+    if let Some(replay) = self.replay.as_mut() {
+      replay.animate();
+    }
+  }
+
+  fn handle_events(&mut self, key_event: KeyEvent) -> bool {
+    if self.replay.is_some() {
+      if key_event.code == KeyCode::Esc {
+        self.replay = None;
+      }
+
+      return true;
+    }
+
+    match key_event.code {
+      KeyCode::Up => self.select_run(self.selected_index.saturating_sub(1)),
+      KeyCode::Down => self.select_run(self.selected_index + 1),
+      KeyCode::PageUp => self.select_run(self.selected_index.saturating_sub(PAGE_ROWS_COUNT)),
+      KeyCode::PageDown => self.select_run(self.selected_index + PAGE_ROWS_COUNT),
+      KeyCode::Home => self.select_run(0),
+      KeyCode::End => self.select_run(usize::MAX),
+      KeyCode::Enter => {
+        self.start_replay();
+        true
+      }
+      KeyCode::Tab => {
+        self.heatmap_mode = self.heatmap_mode.switch();
+        true
+      }
+      KeyCode::Char('m') => {
+        self.is_metrics_visible = !self.is_metrics_visible;
+        true
+      }
+      KeyCode::Char('d') => self.update_stat_filter(|stat_filter, stats| {
+        stat_filter.switch_typing_test(stats);
+      }),
+      KeyCode::Char('l') => self.update_stat_filter(|stat_filter, stats| {
+        stat_filter.switch_lang_code(stats);
+      }),
+      KeyCode::Char('t') => {
+        self.update_stat_filter(|stat_filter, _| stat_filter.switch_date_range())
+      }
+      KeyCode::Char('s') => {
+        self.update_stat_filter(|stat_filter, _| stat_filter.switch_sort_column())
+      }
+      KeyCode::Char('o') => {
+        self.update_stat_filter(|stat_filter, _| stat_filter.toggle_sort_order())
+      }
+      KeyCode::Char('c') => self.update_stat_filter(|stat_filter, _| stat_filter.clear()),
+      _ => false,
+    }
+  }
+
+  fn handle_control_events(&mut self, key_event: KeyEvent) -> bool {
+    let format = match key_event.code {
+      KeyCode::Char('e') => ExportFormat::Csv,
+      KeyCode::Char('o') => ExportFormat::Json,
+      _ => return false,
+    };
+
+    self.status_message = Some(match self.export_stats(format) {
+      Ok(file_path) => format!(" Exported into {} ", file_path.display()),
+      Err(error) => format!(" Export failed: {error} "),
+    });
+
+    true
   }
 
   fn render_instructions(&self, frame: &mut Frame, area: Rect) {
@@ -66,11 +255,60 @@ impl Screen for StatsScreen {
 
     let mut instruction_widget = InstructionWidget::new(&app_layout);
 
-    instruction_widget.add_instruction(Instruction::new(
-      "Exit",
-      "esc",
-      TukaiLayoutColorTypeEnum::Secondary,
-    ));
+    if self.replay.is_some() {
+      instruction_widget.add_instruction(Instruction::new(
+        "Close replay",
+        "esc",
+        TukaiLayoutColorTypeEnum::Secondary,
+      ));
+      instruction_widget.add_instruction(Instruction::new(
+        "Restart replay",
+        "ctrl-r",
+        TukaiLayoutColorTypeEnum::Secondary,
+      ));
+    } else {
+      instruction_widget.add_instruction(Instruction::new(
+        "Exit",
+        "esc",
+        TukaiLayoutColorTypeEnum::Secondary,
+      ));
+      instruction_widget.add_instruction(Instruction::new(
+        "Select run",
+        "↑/↓/pgup/pgdn",
+        TukaiLayoutColorTypeEnum::Secondary,
+      ));
+      instruction_widget.add_instruction(Instruction::new(
+        "Replay",
+        "enter",
+        TukaiLayoutColorTypeEnum::Secondary,
+      ));
+      instruction_widget.add_instruction(Instruction::new(
+        "Heatmap",
+        "tab",
+        TukaiLayoutColorTypeEnum::Secondary,
+      ));
+      instruction_widget.add_instruction(Instruction::new(
+        "Metrics",
+        "m",
+        TukaiLayoutColorTypeEnum::Secondary,
+      ));
+      instruction_widget.add_instruction(Instruction::new(
+        "Filter test/lang/date",
+        "d/l/t",
+        TukaiLayoutColorTypeEnum::Secondary,
+      ));
+      instruction_widget.add_instruction(Instruction::new(
+        "Sort/order",
+        "s/o",
+        TukaiLayoutColorTypeEnum::Secondary,
+      ));
+      instruction_widget.add_instruction(Instruction::new(
+        "Clear filters",
+        "c",
+        TukaiLayoutColorTypeEnum::Secondary,
+      ));
+    }
+
     instruction_widget.add_instruction(Instruction::new(
       "Transparent",
       "ctrl-t",
@@ -81,6 +319,16 @@ impl Screen for StatsScreen {
       "ctrl-h",
       TukaiLayoutColorTypeEnum::Secondary,
     ));
+    instruction_widget.add_instruction(Instruction::new(
+      "Export csv",
+      "ctrl-e",
+      TukaiLayoutColorTypeEnum::Secondary,
+    ));
+    instruction_widget.add_instruction(Instruction::new(
+      "Export json",
+      "ctrl-o",
+      TukaiLayoutColorTypeEnum::Secondary,
+    ));
 
     let block = Block::new().padding(Padding::new(0, 0, area.height / 2, 0));
 
@@ -94,7 +342,10 @@ impl Screen for StatsScreen {
   }
 
   fn render(&self, frame: &mut Frame, area: Rect) {
-    let storage_handler = StorageHandler::new("tukai.bin").init().unwrap();
+    if let Some(replay) = &self.replay {
+      replay.render(frame, area);
+      return;
+    }
 
     let chunks = Layout::default()
       .direction(Direction::Horizontal)
@@ -106,24 +357,33 @@ impl Screen for StatsScreen {
       .constraints(vec![Constraint::Percentage(50), Constraint::Percentage(50)])
       .split(chunks[0]);
 
+    let chart_heatmap_widget = Layout::default()
+      .direction(Direction::Horizontal)
+      .constraints(vec![Constraint::Percentage(55), Constraint::Percentage(45)])
+      .split(left_widget[1]);
+
     let right_widget = Layout::default()
       .direction(Direction::Vertical)
       .constraints(vec![Constraint::Length(7), Constraint::Percentage(100)])
       .split(chunks[1]);
 
-    let last_runs_table_widget_data = storage_handler.get_data_stats_reversed();
-    let last_runs_table_widget = self.get_last_runs_table_widget(&last_runs_table_widget_data);
-
-    let chart_widget_data = storage_handler.get_data_for_chart();
-    let chart_widget = self.get_chart_widget(&chart_widget_data);
+    let stats_data = &self.stats_data;
 
-    let best_score_widget = self.get_best_score_widget(&storage_handler);
+    let last_runs_table_widget = self.get_last_runs_table_widget(&stats_data.sorted_stats);
+    let chart_widget = self.get_chart_widget(&stats_data.chart_data);
+    let heatmap_widget = self.get_heatmap_widget(&stats_data.key_stats);
+    let best_score_widget = self.get_best_score_widget(&stats_data.best_stats);
+    let stats_overview_widget = self.get_stats_overview_widget(&stats_data.stat_overview);
 
-    let chart_widget_data = storage_handler.get_data_for_overview();
-    let stats_overview_widget = self.get_stats_overview_widget(&chart_widget_data);
+    let mut last_runs_table_state = TableState::default().with_selected(Some(self.selected_index));
 
-    frame.render_widget(last_runs_table_widget, left_widget[0]);
-    frame.render_widget(chart_widget, left_widget[1]);
+    frame.render_stateful_widget(
+      last_runs_table_widget,
+      left_widget[0],
+      &mut last_runs_table_state,
+    );
+    frame.render_widget(chart_widget, chart_heatmap_widget[0]);
+    frame.render_widget(heatmap_widget, chart_heatmap_widget[1]);
     frame.render_widget(stats_overview_widget, right_widget[0]);
     frame.render_widget(best_score_widget, right_widget[1]);
   }
@@ -132,16 +392,68 @@ impl Screen for StatsScreen {
 }
 
 impl StatsScreen {
+  /// Selects the run at the index, clamped to the displayed runs
+  fn select_run(&mut self, index: usize) -> bool {
+    let stats_count = self.stats_data.sorted_stats.len();
+
+    self.selected_index = index.min(stats_count.saturating_sub(1));
+    true
+  }
+
+  /// Updates the filter (or sort order) of the displayed runs
+  ///
+  /// The data of the displayed runs are recalculated,
+  /// the first run of the new list is selected.
+  fn update_stat_filter<F>(&mut self, update: F) -> bool
+  where
+    F: FnOnce(&mut StatFilter, &[Stat]),
+  {
+    let Some(storage_handler) = self.storage_handler.as_ref() else {
+      return false;
+    };
+
+    update(&mut self.stat_filter, storage_handler.get_data_stats());
+    self.stats_data = StatsData::new(storage_handler, &self.stat_filter);
+    self.selected_index = 0;
+    true
+  }
+
+  /// Starts the replay of the selected run
+  fn start_replay(&mut self) {
+    let Some(stat) = self.stats_data.sorted_stats.get(self.selected_index) else {
+      return;
+    };
+
+    self.replay = Replay::new(Rc::clone(&self.config), stat.clone());
+
+    if self.replay.is_none() {
+      self.status_message = Some(String::from(" No keystrokes recorded for the run "));
+    }
+  }
+
+  /// Exports all stats into the current directory
+  ///
+  /// Returns the path to the exported file.
+  fn export_stats(&self, format: ExportFormat) -> Result<PathBuf> {
+    let storage_handler = self
+      .storage_handler
+      .as_ref()
+      .ok_or_else(|| anyhow!("The stats are not loaded"))?;
+
+    let file_path = std::env::current_dir()?.join(StatExport::get_file_name(format));
+    StatExport::write_into_file(storage_handler.get_data_stats(), format, &file_path)?;
+
+    Ok(file_path)
+  }
+
   /// Returns the right widget (Best score)
-  fn get_best_score_widget<'a>(&'a self, storage_handler: &StorageHandler) -> Table<'a> {
+  fn get_best_score_widget<'a>(&'a self, stats: &[Stat]) -> Table<'a> {
     let app_config = self.config.borrow();
     let app_layout = &app_config.get_layout();
 
     let primary_color = app_layout.get_primary_color();
     let text_color = app_layout.get_text_color();
 
-    let stats = storage_handler.get_data_stats_best();
-
     let block = Block::new()
       .title(" Best score ")
       .title_style(Style::new().fg(primary_color))
@@ -186,13 +498,20 @@ impl StatsScreen {
     let primary_color = app_layout.get_primary_color();
     let text_color = app_layout.get_text_color();
 
-    let block = Block::new()
+    let mut block = Block::new()
       .title(self.get_title())
       .title_style(Style::new().fg(primary_color))
       .borders(Borders::ALL)
       .border_style(Style::default().fg(primary_color))
       .border_type(BorderType::Rounded);
 
+    let filter_description = format!(" {} ", self.stat_filter.get_description());
+    block = block.title_top(Line::from(filter_description).right_aligned());
+
+    if let Some(status_message) = &self.status_message {
+      block = block.title_bottom(status_message.as_str());
+    }
+
     let default_cell_style = Style::default().fg(app_layout.get_text_color());
 
     let rows = stats
@@ -200,41 +519,94 @@ impl StatsScreen {
       .map(|stat| {
         let duration_pretty = stat.get_duration_pretty();
 
-        Row::new(vec![
+        let lang_code = match stat.get_lang_code() {
+          "" => "-",
+          lang_code => lang_code,
+        };
+
+        let mut cells = vec![
+          Cell::from(stat.get_completed_at_pretty())
+            .style(Style::default().fg(text_color.to_dark())),
           Cell::from(duration_pretty),
+          Cell::from(lang_code.to_string()).style(default_cell_style),
           Cell::from(stat.get_average_wpm().to_string()).style(default_cell_style),
           Cell::from(format!("{}%", stat.get_accuracy())).style(default_cell_style),
           Cell::from(stat.get_raw_wpm().to_string())
             .style(Style::default().fg(text_color.to_dark())),
           Cell::from(format!("{}%", stat.get_true_accuracy()))
             .style(Style::default().fg(text_color.to_dark())),
-        ])
+        ];
+
+        if self.is_metrics_visible {
+          // Runs recorded without the keystrokes have no metrics
+          let metrics_values = match stat.get_metrics() {
+            Some(metrics) => [
+              format!("{:.0}%", metrics.get_consistency()),
+              metrics.get_burst_wpm().to_string(),
+              metrics.get_longest_streak().to_string(),
+              format!(
+                "{}/{}",
+                metrics.get_corrected_errors(),
+                metrics.get_uncorrected_errors()
+              ),
+            ],
+            None => ["-", "-", "-", "-"].map(String::from),
+          };
+
+          cells.extend(
+            metrics_values
+              .into_iter()
+              .map(|value| Cell::from(value).style(Style::default().fg(text_color.to_dark()))),
+          );
+        }
+
+        Row::new(cells)
       })
       .collect::<Vec<Row>>();
 
-    let widths = [
-      Constraint::Percentage(20),
-      Constraint::Percentage(20),
-      Constraint::Percentage(20),
-      Constraint::Percentage(20),
-      Constraint::Percentage(20),
+    let mut widths = vec![
+      Constraint::Length(16),
+      Constraint::Length(16),
+      Constraint::Length(11),
+      Constraint::Fill(1),
+      Constraint::Fill(1),
+      Constraint::Fill(1),
+      Constraint::Fill(1),
+    ];
+
+    let mut header_titles = vec![
+      "📅 Date",
+      "⏳ Duration",
+      "🌍 Language",
+      "🔥 Average WPM",
+      "🎯 Accuracy",
+      "🥩 Raw WPM",
+      "🥶 True Accuracy",
     ];
 
+    if self.is_metrics_visible {
+      widths.extend([Constraint::Fill(1); 4]);
+      header_titles.extend([
+        "📈 Consistency",
+        "💥 Burst WPM",
+        "🔗 Streak",
+        "🩹 Fixed/Left",
+      ]);
+    }
+
     let default_header_cell_style = Style::default().fg(primary_color).bold();
 
     Table::new(rows, widths)
       .block(block)
       .column_spacing(1)
       .style(app_config.get_bg_color())
-      .highlight_symbol("X")
+      .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED))
       .header(
-        Row::new(vec![
-          Cell::from("⏳ Duration").style(default_header_cell_style),
-          Cell::from("🔥 Average WPM").style(default_header_cell_style),
-          Cell::from("🎯 Accuracy").style(default_header_cell_style),
-          Cell::from("🥩 Raw WPM").style(default_header_cell_style),
-          Cell::from("🥶 True Accuracy").style(default_header_cell_style),
-        ])
+        Row::new(
+          header_titles
+            .into_iter()
+            .map(|title| Cell::from(title).style(default_header_cell_style)),
+        )
         .bottom_margin(1),
       )
   }
@@ -288,6 +660,127 @@ impl StatsScreen {
       .y_axis(y_axis)
   }
 
+  /// Gets the keyboard-shaped heatmap of the error rate or latency per key
+  fn get_heatmap_widget<'a>(&self, key_stats: &KeyStats) -> Paragraph<'a> {
+    let app_config = self.config.borrow();
+    let app_layout = &app_config.get_layout();
+
+    let primary_color = app_layout.get_primary_color();
+    let error_color = app_layout.get_error_color();
+    let text_color = app_layout.get_text_color();
+    let background_color = app_layout.get_background_color();
+
+    let get_key_value = |c: char| -> Option<f64> {
+      let key_stat = key_stats.get_key_stat(c)?;
+
+      match self.heatmap_mode {
+        HeatmapMode::Errors => Some(key_stat.get_error_rate()),
+        HeatmapMode::Latency => key_stat
+          .get_average_latency_ms()
+          .map(|latency_ms| latency_ms as f64),
+      }
+    };
+
+    let key_values = HEATMAP_KEYBOARD_ROWS
+      .iter()
+      .flat_map(|row| row.chars())
+      .filter_map(get_key_value)
+      .collect::<Vec<f64>>();
+
+    // Errors are compared from zero, latencies from the fastest key
+    let min_value = match self.heatmap_mode {
+      HeatmapMode::Errors => 0.0,
+      HeatmapMode::Latency => key_values.iter().copied().fold(f64::MAX, f64::min),
+    };
+
+    let max_value = key_values.iter().copied().fold(0.0, f64::max);
+
+    let mut lines = vec![Line::default()];
+
+    for (row_index, row) in HEATMAP_KEYBOARD_ROWS.iter().enumerate() {
+      let mut spans = vec![Span::from(" ".repeat(row_index + 1))];
+
+      spans.extend(row.chars().map(|c| {
+        let key_span = Span::from(format!(" {c} "));
+
+        match get_key_value(c) {
+          Some(value) => {
+            let ratio = if max_value > min_value {
+              (value - min_value) / (max_value - min_value)
+            } else {
+              0.0
+            };
+
+            key_span.style(
+              Style::default()
+                .fg(background_color)
+                .bg(get_interpolated_color(primary_color, error_color, ratio))
+                .bold(),
+            )
+          }
+          None => key_span.style(Style::default().fg(text_color.to_dark())),
+        }
+      }));
+
+      lines.push(Line::from(spans));
+    }
+
+    lines.push(Line::default());
+
+    let (summary_title, summary_keys) = match self.heatmap_mode {
+      HeatmapMode::Errors => (
+        " Worst: ",
+        key_stats
+          .get_worst_keys()
+          .iter()
+          .take(3)
+          .map(|(c, key_stat)| format!("{c} {:.0}%", key_stat.get_error_rate() * 100.0))
+          .collect::<Vec<String>>(),
+      ),
+      HeatmapMode::Latency => (
+        " Slowest: ",
+        key_stats
+          .get_slowest_keys()
+          .iter()
+          .take(3)
+          .filter_map(|(c, key_stat)| {
+            key_stat
+              .get_average_latency_ms()
+              .map(|latency_ms| format!("{c} {latency_ms}ms"))
+          })
+          .collect::<Vec<String>>(),
+      ),
+    };
+
+    if key_stats.is_empty() {
+      lines.push(Line::from(
+        Span::from(" No keystrokes recorded yet").style(Style::default().fg(text_color)),
+      ));
+    } else if !summary_keys.is_empty() {
+      lines.push(Line::from(vec![
+        Span::from(summary_title).style(Style::default().fg(text_color)),
+        Span::from(summary_keys.join(" · ")).style(Style::default().fg(primary_color).bold()),
+      ]));
+    }
+
+    let title = match self.heatmap_mode {
+      HeatmapMode::Errors => " Key errors ",
+      HeatmapMode::Latency => " Key latency ",
+    };
+
+    let block = Block::new()
+      .title(title)
+      .title_style(Style::new().fg(primary_color))
+      .borders(Borders::ALL)
+      .border_style(Style::default().fg(primary_color))
+      .border_type(BorderType::Rounded);
+
+    Paragraph::new(lines)
+      .block(block)
+      .style(app_config.get_bg_color())
+      .alignment(Alignment::Left)
+  }
+
   fn get_stats_overview_widget<'a>(&self, stat_overview: &'a StatOverview) -> Paragraph<'a> {
     let app_config = self.config.borrow();
     let app_layout = &app_config.get_layout();
@@ -327,3 +820,25 @@ impl StatsScreen {
       .alignment(Alignment::Left)
   }
 }
+
+/// Returns the color between the colors by the ratio (0.0 - 1.0)
+///
+/// Non-RGB colors are not blended, the closer one is returned.
+fn get_interpolated_color(from: Color, to: Color, ratio: f64) -> Color {
+  let ratio = ratio.clamp(0.0, 1.0);
+
+  match (from, to) {
+    (Color::Rgb(from_r, from_g, from_b), Color::Rgb(to_r, to_g, to_b)) => {
+      let blend =
+        |from: u8, to: u8| (from as f64 + (to as f64 - from as f64) * ratio).round() as u8;
+
+      Color::Rgb(
+        blend(from_r, to_r),
+        blend(from_g, to_g),
+        blend(from_b, to_b),
+      )
+    }
+    _ if ratio < 0.5 => from,
+    _ => to,
+  }
+}
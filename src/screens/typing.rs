@@ -1,78 +1,112 @@
-use std::{cell::RefCell, collections::HashSet, rc::Rc};
+use std::{
+  cell::RefCell,
+  ops::Range,
+  rc::Rc,
+  time::{Duration, Instant},
+};
 
 use ratatui::{
   Frame,
   crossterm::event::{KeyCode, KeyEvent},
   layout::{Alignment, Constraint, Flex, Layout, Rect},
-  style::{Modifier, Style, Stylize},
+  style::{Color, Modifier, Style, Stylize},
+  symbols,
   text::{Line, Span, Text},
-  widgets::{Block, BorderType, Borders, Clear, Padding, Paragraph, Wrap},
+  widgets::{
+    Axis, Block, BorderType, Borders, Chart, Clear, Dataset, GraphType, Padding, Paragraph,
+  },
 };
 
 use crate::{
-  config::{TukaiConfig, TukaiLayout, TukaiLayoutColorTypeEnum},
+  config::{TukaiConfig, TukaiLayout, TukaiLayoutColorTypeEnum, TypingDuration, TypingMode},
+  graphemes::GraphemeText,
   helper::Generator,
-  screens::{Instruction, InstructionWidget, Screen, ToDark},
-  storage::{stats::Stat, storage_handler::StorageHandler},
+  screens::{
+    Instruction, InstructionWidget, Screen, ToDark,
+    typing_input::{StyledGrapheme, TypingInput},
+  },
+  snippet::{INDENT_WIDTH, TokenKind},
+  storage::{
+    keystroke::{Keystroke, KeystrokeKind, KeystrokeLog},
+    stat_helper::StatHelper,
+    stats::{RunMetrics, Stat},
+    storage_handler::StorageHandler,
+    wpm_timeline::WpmTimeline,
+  },
 };
 
 use super::ActiveScreenEnum;
 
-/// Handler for incorrect symbols
+/// Count of the untyped chars, below which the zen and timed runs append more words
+const APPEND_REMAINING_CHARS: usize = 40;
+
+/// Count of the text lines visible around the cursor
+const VIEWPORT_LINES: usize = 3;
+
+/// Shortest time the live WPM is calculated from,
+/// so the first keystrokes do not show an absurd speed.
+const LIVE_WPM_MIN_ELAPSED: Duration = Duration::from_secs(1);
+
+/// Wraps the graphemes into the lines of the width
 ///
-/// Inserts incorrect characters into a HashSet
-pub struct MistakeHandler {
-  mistakes_indexes: HashSet<usize>,
-  true_mistakes_indexes: HashSet<usize>,
-}
+/// Returns the grapheme index ranges of the lines.
+/// Lines are broken after a space (or a newline),
+/// a word longer than the line is broken anywhere.
+/// A trailing space always fits into the line, so the cursor stays visible.
+fn get_wrapped_lines(graphemes: &[&str], width: u16) -> Vec<Range<usize>> {
+  // One column is kept for the trailing space
+  let width = (width as usize).saturating_sub(1).max(1);
+
+  let mut lines = Vec::new();
+  let mut line_start = 0;
+  let mut last_break = None;
+
+  for (i, grapheme) in graphemes.iter().enumerate() {
+    if *grapheme == "\n" {
+      lines.push(line_start..i + 1);
+      line_start = i + 1;
+      last_break = None;
+      continue;
+    }
 
-impl MistakeHandler {
-  /// Creates MistakeHandler with empty HashSet
-  fn new() -> Self {
-    Self {
-      mistakes_indexes: HashSet::new(),
-      true_mistakes_indexes: HashSet::new(),
+    if *grapheme != " " && i - line_start >= width {
+      let break_index = last_break.unwrap_or(i);
+
+      lines.push(line_start..break_index);
+      line_start = break_index;
+      last_break = None;
     }
-  }
 
-  /// Verifies if the character is mistaken
-  pub fn is_char_mistaken(&self, char_index: usize) -> bool {
-    self.mistakes_indexes.contains(&char_index)
+    if *grapheme == " " {
+      last_break = Some(i + 1);
+    }
   }
 
-  /// Adds the typed character into the mistakes
-  pub fn add_to_mistakes_indexes(&mut self, char_index: usize) -> bool {
-    self.true_mistakes_indexes.insert(char_index);
-    self.mistakes_indexes.insert(char_index)
+  if line_start < graphemes.len() || lines.is_empty() {
+    lines.push(line_start..graphemes.len());
   }
 
-  /// Removes the typed character from mistakes
-  pub fn remove_from_mistakes_indexes(&mut self, char_index: usize) -> bool {
-    self.mistakes_indexes.remove(&char_index)
-  }
+  lines
+}
 
-  /// Returns the current mistake count
-  pub fn get_mistakes_counter(&self) -> usize {
-    self.mistakes_indexes.len()
-  }
+/// Text of the run with its source
+struct RunText {
+  // Text to type
+  text: String,
 
-  pub fn get_true_mistakes_counter(&self) -> usize {
-    self.true_mistakes_indexes.len()
-  }
+  // Attribution of the quote or the name of the snippet
+  source: Option<String>,
+
+  // Token kinds of the code snippet chars (empty for other texts)
+  token_kinds: Vec<TokenKind>,
 }
 
 pub struct TypingScreen {
   /// Application config
   config: Rc<RefCell<TukaiConfig>>,
 
-  /// Random generated text from a words list
-  pub generated_text: String,
-
-  /// User typed input
-  pub input: String,
-
-  /// Handle incorrect characters
-  pub mistake_handler: MistakeHandler,
+  /// Text to type and the user typed input
+  typing_input: TypingInput,
 
   /// User statistics after the current run is completed
   pub stat: Option<Stat>,
@@ -83,27 +117,67 @@ pub struct TypingScreen {
   /// Popup is visible
   is_popup_visible: bool,
 
-  pub time_secs: u32,
+  /// Duration of the current run
+  typing_duration: TypingDuration,
+
+  /// Type of the current run
+  typing_mode: TypingMode,
+
+  /// User finished the zen run
+  is_finish_requested: bool,
+
+  /// Attribution of the typed quote or the name of the typed snippet
+  text_source: Option<String>,
+
+  /// Token kinds of the typed snippet (code runs only)
+  token_kinds: Vec<TokenKind>,
+
+  /// Start of the current run (the first keystroke)
+  run_started_at: Option<Instant>,
+
+  /// End of the stopped run
+  run_ended_at: Option<Instant>,
+
+  /// Time of the last keystroke of the current run
+  last_keystroke_at: Option<Instant>,
 
-  /// The current cursor index withing generated_text
-  cursor_index: usize,
+  /// Keystrokes of the personal best run (ghost cursor)
+  ghost: Option<KeystrokeLog>,
 
   /// Block motto
   motto: String,
 }
 
 impl TypingScreen {
-  pub fn new(config: Rc<RefCell<TukaiConfig>>) -> Self {
-    let generated_text = Generator::generate_random_string(&config.borrow());
+  pub fn new(config: Rc<RefCell<TukaiConfig>>, storage_handler: &StorageHandler) -> Self {
+    let run_text = TypingScreen::generate_text(&config.borrow());
+    let typing_duration = config.borrow().typing_duration.clone();
+    let typing_mode = config.borrow().typing_mode;
+    let ghost = TypingScreen::load_ghost(&config.borrow(), storage_handler);
 
-    Self {
+    TypingScreen::with_run_text(
       config,
+      run_text,
+      typing_duration,
+      typing_mode,
+      ghost,
+      Generator::generate_random_motto(),
+    )
+  }
 
-      generated_text,
-
-      input: String::new(),
+  /// Creates a TypingScreen of the run text
+  fn with_run_text(
+    config: Rc<RefCell<TukaiConfig>>,
+    run_text: RunText,
+    typing_duration: TypingDuration,
+    typing_mode: TypingMode,
+    ghost: Option<KeystrokeLog>,
+    motto: String,
+  ) -> Self {
+    Self {
+      config,
 
-      mistake_handler: MistakeHandler::new(),
+      typing_input: TypingInput::new(run_text.text),
 
       stat: None,
 
@@ -111,31 +185,161 @@ impl TypingScreen {
 
       is_popup_visible: false,
 
-      time_secs: 0,
+      typing_duration,
+
+      typing_mode,
+
+      is_finish_requested: false,
+
+      text_source: run_text.source,
 
-      cursor_index: 0,
+      token_kinds: run_text.token_kinds,
 
-      motto: Generator::generate_random_motto(),
+      run_started_at: None,
+
+      run_ended_at: None,
+
+      last_keystroke_at: None,
+
+      ghost,
+
+      motto,
     }
   }
-}
 
-impl Screen for TypingScreen {
-  fn increment_time_secs(&mut self) {
-    self.time_secs += 1;
+  /// Creates a TypingScreen replaying the recorded run
+  ///
+  /// The text of the run is typed by the recorded keystrokes,
+  /// which are applied by `apply_keystroke`.
+  /// The clock runs from now until the elapsed time of the run.
+  pub fn new_replay(config: Rc<RefCell<TukaiConfig>>, stat: &Stat) -> Self {
+    let run_text = RunText {
+      text: stat.get_text().to_string(),
+      source: None,
+      token_kinds: Vec::new(),
+    };
+
+    let mut typing_screen = TypingScreen::with_run_text(
+      config,
+      run_text,
+      stat.get_typing_duration().clone(),
+      stat.get_typing_mode(),
+      None,
+      format!(
+        " Replay of the run from {} ",
+        stat.get_completed_at_pretty()
+      ),
+    );
+
+    let replay_started_at = Instant::now();
+
+    typing_screen.is_running = true;
+    typing_screen.run_started_at = Some(replay_started_at);
+    typing_screen.run_ended_at = Some(replay_started_at + stat.get_elapsed());
+
+    typing_screen
+  }
+
+  /// Generates the text of the run
+  ///
+  /// Quote runs return the quote with its attribution,
+  /// code runs the snippet with its name and token kinds.
+  /// If there is no quote (or snippet), falls back to the random words.
+  fn generate_text(config: &TukaiConfig) -> RunText {
+    match config.typing_mode {
+      TypingMode::Quote => {
+        if let Some(quote) = Generator::generate_quote(config) {
+          return RunText {
+            text: quote.get_text().to_string(),
+            source: Some(quote.get_source().to_string()),
+            token_kinds: Vec::new(),
+          };
+        }
+      }
+      TypingMode::Code => {
+        if let Some(snippet) = Generator::generate_snippet(config) {
+          return RunText {
+            text: snippet.get_code().to_string(),
+            source: Some(format!("{} · {}", snippet.get_lang(), snippet.get_name())),
+            token_kinds: snippet.get_token_kinds(),
+          };
+        }
+      }
+      _ => {}
+    }
+
+    RunText {
+      text: Generator::generate_random_string(config),
+      source: None,
+      token_kinds: Vec::new(),
+    }
+  }
+
+  /// Loads the keystrokes of the personal best run
+  /// for the selected test and language.
+  ///
+  /// None if the ghost cursor is disabled, the run is zen, quote or code (the text differs)
+  /// or no run was recorded yet.
+  fn load_ghost(config: &TukaiConfig, storage_handler: &StorageHandler) -> Option<KeystrokeLog> {
+    if !config.has_ghost
+      || matches!(
+        config.typing_mode,
+        TypingMode::Zen | TypingMode::Quote | TypingMode::Code
+      )
+    {
+      return None;
+    }
+
+    storage_handler
+      .get_personal_best(
+        &config.typing_mode,
+        &config.typing_duration,
+        config.get_language().get_lang_code(),
+      )
+      .map(|stat| stat.get_keystroke_log().clone())
   }
+}
 
+impl Screen for TypingScreen {
   fn get_config(&self) -> &Rc<RefCell<TukaiConfig>> {
     &self.config
   }
 
+  /// Returns the remaining time of the timed run (seconds, rounded up)
+  ///
+  /// The word count and zen runs are not limited by time.
   fn get_remaining_time(&self) -> usize {
-    let app_config = &self.config.borrow();
+    if self.typing_mode != TypingMode::Time {
+      return usize::MAX;
+    }
 
-    app_config
+    let remaining_time = self
       .typing_duration
-      .as_seconds()
-      .saturating_sub(self.time_secs as usize)
+      .as_duration()
+      .saturating_sub(self.get_elapsed());
+
+    remaining_time.as_millis().div_ceil(1000) as usize
+  }
+
+  /// Returns whether the run is over
+  ///
+  /// - `Time`: the time is over
+  /// - `Words`, `Quote`, `Code`: the whole text was typed
+  /// - `Zen`: the user finished the run
+  fn is_finished(&self) -> bool {
+    match self.typing_mode {
+      TypingMode::Time => self.get_remaining_time() == 0,
+      TypingMode::Words(_) | TypingMode::Quote | TypingMode::Code => {
+        self.run_started_at.is_some()
+          && self.typing_input.get_cursor_index()
+            >= self
+              .typing_input
+              .get_text()
+              .trim_end()
+              .get_graphemes_count()
+      }
+      TypingMode::Zen => self.is_finish_requested,
+    }
   }
 
   fn get_next_screen(&self) -> Option<ActiveScreenEnum> {
@@ -147,17 +351,36 @@ impl Screen for TypingScreen {
   /// Makes the popup screen visible
   ///
   /// Inserts the created stat into storage
+  ///
+  /// The timed run ends with the duration, others with the last keystroke.
   fn stop(&mut self, storage_handler: &mut StorageHandler) {
     self.is_running = false;
     self.is_popup_visible = true;
 
+    if self.run_ended_at.is_none() {
+      self.run_ended_at = match (self.typing_mode, self.run_started_at) {
+        (TypingMode::Time, Some(run_started_at)) => {
+          Some(run_started_at + self.typing_duration.as_duration())
+        }
+        _ => self.last_keystroke_at,
+      };
+    }
+
     if self.stat.is_none() {
+      let elapsed = self.get_elapsed();
+      let app_config = self.config.borrow();
+
       let stat = Stat::new(
-        self.config.borrow().typing_duration.clone(),
-        self.input.len(),
-        self.mistake_handler.get_mistakes_counter(),
-        self.mistake_handler.get_true_mistakes_counter(),
-      );
+        self.typing_duration.clone(),
+        elapsed,
+        self.typing_input.get_char_counts(),
+        self.typing_input.get_corrected_errors_count(),
+      )
+      .lang_code(app_config.get_language().get_lang_code().as_str())
+      .screen(ActiveScreenEnum::Typing)
+      .typing_mode(self.typing_mode)
+      .text(self.typing_input.get_text())
+      .keystroke_log(self.typing_input.take_keystroke_log());
 
       storage_handler.insert_into_stats(&stat);
 
@@ -170,12 +393,20 @@ impl Screen for TypingScreen {
     self.is_running
   }
 
+  /// Redraws the moving ghost cursor
+  fn is_animated(&self) -> bool {
+    self.is_running && self.ghost.is_some()
+  }
+
   fn is_popup_visible(&self) -> bool {
     self.is_popup_visible
   }
 
   fn get_screen_name(&self) -> String {
-    String::from("Typing")
+    match self.typing_mode {
+      TypingMode::Time => String::from("Typing"),
+      typing_mode => format!("Typing 》{typing_mode}"),
+    }
   }
 
   fn handle_control_events(&mut self, key_event: KeyEvent) -> bool {
@@ -194,39 +425,65 @@ impl Screen for TypingScreen {
   }
 
   /// Resets all necessary properties
-  fn reset(&mut self) {
+  fn reset(&mut self, storage_handler: &StorageHandler) {
     self.is_running = false;
-    self.time_secs = 0;
 
-    self.mistake_handler = MistakeHandler::new();
-    self.cursor_index = 0;
-    self.input = String::new();
     self.is_popup_visible = false;
+    self.is_finish_requested = false;
+
+    self.run_started_at = None;
+    self.run_ended_at = None;
+    self.last_keystroke_at = None;
 
     let app_config = self.config.borrow();
-    self.generated_text = Generator::generate_random_string(&app_config);
+    let run_text = TypingScreen::generate_text(&app_config);
+    self.typing_input = TypingInput::new(run_text.text);
+    self.text_source = run_text.source;
+    self.token_kinds = run_text.token_kinds;
+    self.typing_duration = app_config.typing_duration.clone();
+    self.typing_mode = app_config.typing_mode;
+    self.ghost = TypingScreen::load_ghost(&app_config, storage_handler);
   }
 
   fn handle_events(&mut self, key_event: KeyEvent) -> bool {
-    if self.cursor_index > 0 && !self.is_running() {
+    // The stopped run is not typed anymore
+    if self.run_started_at.is_some() && !self.is_running() {
       return false;
     }
 
     match key_event.code {
-      KeyCode::Esc => {
-        if self.is_popup_visible() {
-          self.is_popup_visible = false;
-          true
-        } else {
-          false
-        }
+      KeyCode::Esc if self.is_popup_visible() => {
+        self.is_popup_visible = false;
+        true
       }
       KeyCode::Char(c) => {
-        if self.cursor_index == 0 {
+        if self.run_started_at.is_none() {
           self.run();
         }
 
         self.move_cursor_forward_with(c);
+
+        if matches!(self.typing_mode, TypingMode::Zen | TypingMode::Time) {
+          self.append_words();
+        }
+
+        true
+      }
+      KeyCode::Enter | KeyCode::Tab if self.typing_mode == TypingMode::Code => {
+        if self.run_started_at.is_none() {
+          self.run();
+        }
+
+        if key_event.code == KeyCode::Enter {
+          self.type_code_newline();
+        } else {
+          self.type_code_indent();
+        }
+
+        true
+      }
+      KeyCode::Enter if self.typing_mode == TypingMode::Zen && self.is_running => {
+        self.is_finish_requested = true;
         true
       }
       KeyCode::Backspace => {
@@ -247,7 +504,7 @@ impl Screen for TypingScreen {
       area.width / 3 - 8
     };
 
-    let block = Block::new()
+    let mut block = Block::new()
       .title(self.get_title())
       .title_alignment(Alignment::Left)
       .title_bottom(self.motto.as_ref())
@@ -260,12 +517,22 @@ impl Screen for TypingScreen {
       .padding(Padding::new(
         horizontal_padding,
         horizontal_padding,
-        (area.height / 2) - 5,
+        (area.height / 2).saturating_sub(5),
         0,
       ));
 
+    if let Some(text_source) = self
+      .text_source
+      .as_ref()
+      .filter(|source| !source.is_empty())
+    {
+      block = block.title(Line::from(format!(" — {text_source} ")).right_aligned());
+    }
+
+    let text_width = block.inner(area).width;
+
     let p = self
-      .get_paragraph(&app_layout)
+      .get_paragraph(&app_layout, text_width)
       .block(block)
       .alignment(Alignment::Left);
 
@@ -288,9 +555,39 @@ impl Screen for TypingScreen {
       "ctrl-r",
       TukaiLayoutColorTypeEnum::Secondary,
     ));
+    match self.typing_mode {
+      TypingMode::Time => {
+        instruction_widget.add_instruction(Instruction::new(
+          "Duration",
+          "ctrl-d",
+          TukaiLayoutColorTypeEnum::Secondary,
+        ));
+        instruction_widget.add_instruction(Instruction::new(
+          "Custom",
+          "ctrl-u",
+          TukaiLayoutColorTypeEnum::Secondary,
+        ));
+      }
+      TypingMode::Words(_) => instruction_widget.add_instruction(Instruction::new(
+        "Words",
+        "ctrl-d",
+        TukaiLayoutColorTypeEnum::Secondary,
+      )),
+      TypingMode::Zen => instruction_widget.add_instruction(Instruction::new(
+        "Finish",
+        "enter",
+        TukaiLayoutColorTypeEnum::Secondary,
+      )),
+      TypingMode::Quote => {}
+      TypingMode::Code => instruction_widget.add_instruction(Instruction::new(
+        "Auto indent",
+        "ctrl-a",
+        TukaiLayoutColorTypeEnum::Secondary,
+      )),
+    }
     instruction_widget.add_instruction(Instruction::new(
-      "Duration",
-      "ctrl-d",
+      "Mode",
+      "ctrl-n",
       TukaiLayoutColorTypeEnum::Secondary,
     ));
     instruction_widget.add_instruction(Instruction::new(
@@ -313,6 +610,11 @@ impl Screen for TypingScreen {
       "ctrl-p",
       TukaiLayoutColorTypeEnum::Secondary,
     ));
+    instruction_widget.add_instruction(Instruction::new(
+      "Ghost",
+      "ctrl-g",
+      TukaiLayoutColorTypeEnum::Secondary,
+    ));
 
     let block = Block::new().padding(Padding::new(0, 0, area.height / 2, 0));
 
@@ -328,16 +630,77 @@ impl Screen for TypingScreen {
   /// Renders a popup screen
   ///
   /// Used after the run is completed
+  /// Renders the results of the run with the WPM chart
   fn render_popup(&self, frame: &mut Frame) {
     let app_config = self.config.borrow();
     let app_layout = app_config.get_layout();
     let area = frame.area();
 
+    // Samples of the recorded keystrokes
+    let samples = self
+      .stat
+      .as_ref()
+      .map(|stat| WpmTimeline::from(stat.get_keystroke_log()).get_samples(stat.get_elapsed()))
+      .unwrap_or_default();
+
+    let raw_wpm_data = samples
+      .iter()
+      .map(|sample| (sample.get_second() as f64, sample.get_raw_wpm()))
+      .collect::<Vec<(f64, f64)>>();
+
+    let wpm_data = samples
+      .iter()
+      .map(|sample| (sample.get_second() as f64, sample.get_wpm()))
+      .collect::<Vec<(f64, f64)>>();
+
+    // Errors are marked on the raw WPM of the second
+    let errors_data = samples
+      .iter()
+      .filter(|sample| sample.get_errors_count() > 0)
+      .map(|sample| (sample.get_second() as f64, sample.get_raw_wpm()))
+      .collect::<Vec<(f64, f64)>>();
+
     let block = Block::bordered()
       .style(app_config.get_bg_color())
       .border_type(BorderType::Rounded)
       .border_style(Style::new().fg(app_layout.get_primary_color()));
 
+    let metrics = self.get_calculated_metrics();
+
+    // Runs without keystrokes have no metrics
+    let get_metric = |get_value: fn(&RunMetrics) -> String| {
+      Span::from(metrics.map_or(String::from("-"), get_value)).bold()
+    };
+
+    let metrics_text = Text::from(vec![
+      Line::from(vec![
+        Span::from("📈 Consistency: "),
+        get_metric(|metrics| format!("{:.0}%", metrics.get_consistency())),
+      ])
+      .style(Style::default().fg(app_layout.get_primary_color())),
+      Line::from(vec![
+        Span::from("💥 Burst WPM: "),
+        get_metric(|metrics| metrics.get_burst_wpm().to_string()),
+      ])
+      .style(Style::default().fg(app_layout.get_primary_color())),
+      Line::from(vec![
+        Span::from("🔗 Longest streak: "),
+        get_metric(|metrics| metrics.get_longest_streak().to_string()),
+      ])
+      .style(Style::default().fg(app_layout.get_primary_color().to_dark())),
+      Line::from(vec![
+        Span::from("🩹 Errors: "),
+        get_metric(|metrics| {
+          format!(
+            "{} fixed, {} left",
+            metrics.get_corrected_errors(),
+            metrics.get_uncorrected_errors()
+          )
+        }),
+      ])
+      .style(Style::default().fg(app_layout.get_primary_color().to_dark())),
+    ]);
+
     let text = Text::from(vec![
       Line::from(vec![
         Span::from("🔥 Average WPM: "),
@@ -359,110 +722,321 @@ impl Screen for TypingScreen {
         Span::from(format!("{}%", self.get_true_calculated_accuracy())).bold(),
       ])
       .style(Style::default().fg(app_layout.get_primary_color().to_dark())),
-      Line::from(""),
-      Line::from(vec![
-        Span::from("Try again").style(Style::default().fg(app_layout.get_primary_color())),
-        Span::from(" ctrl-r").style(Style::default().fg(app_layout.get_primary_color()).bold()),
-      ]),
     ]);
 
-    let p = Paragraph::new(text)
-      .block(block)
-      .alignment(Alignment::Center)
-      .centered();
+    let char_counts = self.typing_input.get_char_counts();
+
+    let char_counts_line = Line::from(vec![
+      Span::from("🔤 Characters: "),
+      Span::from(format!(
+        "{} / {} / {} / {}",
+        char_counts.get_correct(),
+        char_counts.get_incorrect(),
+        char_counts.get_extra(),
+        char_counts.get_missed()
+      ))
+      .bold(),
+      Span::from(" (correct / incorrect / extra / missed)"),
+    ])
+    .style(Style::default().fg(app_layout.get_primary_color().to_dark()));
+
+    let try_again_line = Line::from(vec![
+      Span::from("Try again").style(Style::default().fg(app_layout.get_primary_color())),
+      Span::from(" ctrl-r").style(Style::default().fg(app_layout.get_primary_color()).bold()),
+    ]);
 
-    let vertical = Layout::vertical([Constraint::Percentage(22)]).flex(Flex::Center);
-    let horizontal = Layout::horizontal([Constraint::Percentage(22)]).flex(Flex::Center);
+    let vertical = Layout::vertical([Constraint::Length(23)]).flex(Flex::Center);
+    let horizontal = Layout::horizontal([Constraint::Percentage(50)]).flex(Flex::Center);
     let [area] = vertical.areas(area);
     let [area] = horizontal.areas(area);
 
+    let [text_area, char_counts_area, try_again_area, chart_area] = Layout::vertical([
+      Constraint::Length(4),
+      Constraint::Length(2),
+      Constraint::Length(2),
+      Constraint::Min(0),
+    ])
+    .areas(block.inner(area));
+
+    let [text_area, metrics_text_area] =
+      Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)]).areas(text_area);
+
     frame.render_widget(Clear, area);
-    frame.render_widget(p, area);
+    frame.render_widget(block, area);
+    frame.render_widget(Paragraph::new(text).centered(), text_area);
+    frame.render_widget(Paragraph::new(metrics_text).centered(), metrics_text_area);
+    frame.render_widget(
+      Paragraph::new(char_counts_line).centered(),
+      char_counts_area,
+    );
+    frame.render_widget(Paragraph::new(try_again_line).centered(), try_again_area);
+    frame.render_widget(
+      self.get_wpm_chart(&app_layout, &raw_wpm_data, &wpm_data, &errors_data),
+      chart_area,
+    );
   }
 }
 
 impl TypingScreen {
+  /// Returns the chart of the raw WPM and WPM by seconds
+  /// with the markers of the seconds with errors.
+  fn get_wpm_chart<'a>(
+    &self,
+    layout: &TukaiLayout,
+    raw_wpm_data: &'a [(f64, f64)],
+    wpm_data: &'a [(f64, f64)],
+    errors_data: &'a [(f64, f64)],
+  ) -> Chart<'a> {
+    let primary_color = layout.get_primary_color();
+    let text_color = layout.get_text_color();
+
+    let seconds_count = wpm_data.len().max(1) as f64;
+
+    // Rounded up to tens, so the top line is not glued to the border
+    let max_wpm = raw_wpm_data
+      .iter()
+      .chain(wpm_data)
+      .map(|(_, wpm)| *wpm)
+      .fold(10.0, f64::max);
+    let max_wpm = (max_wpm / 10.0).ceil() * 10.0;
+
+    let get_label = |value: f64| Span::from(format!("{value:.0}")).style(text_color);
+
+    let datasets = vec![
+      Dataset::default()
+        .name("raw")
+        .marker(symbols::Marker::Braille)
+        .graph_type(GraphType::Line)
+        .style(Style::default().fg(text_color))
+        .data(raw_wpm_data),
+      Dataset::default()
+        .name("wpm")
+        .marker(symbols::Marker::Braille)
+        .graph_type(GraphType::Line)
+        .style(Style::default().fg(primary_color))
+        .data(wpm_data),
+      Dataset::default()
+        .name("errors")
+        .marker(symbols::Marker::Dot)
+        .graph_type(GraphType::Scatter)
+        .style(Style::default().fg(layout.get_error_color()))
+        .data(errors_data),
+    ];
+
+    let x_axis = Axis::default()
+      .style(Style::default().fg(text_color))
+      .bounds([1.0, seconds_count])
+      .labels([get_label(1.0), get_label(seconds_count)]);
+
+    let y_axis = Axis::default()
+      .style(Style::default().fg(primary_color))
+      .bounds([0.0, max_wpm])
+      .labels([get_label(0.0), get_label(max_wpm / 2.0), get_label(max_wpm)]);
+
+    Chart::new(datasets)
+      .block(
+        Block::new()
+          .title_top(" WPM by seconds ")
+          .title_style(Style::new().fg(primary_color))
+          .borders(Borders::TOP)
+          .border_style(Style::default().fg(primary_color.to_dark())),
+      )
+      .x_axis(x_axis)
+      .y_axis(y_axis)
+  }
+
   /// Starts the running typing process
   ///
+  /// Called by the first keystroke of the run only,
+  /// the input is fresh after `reset` (or `new`).
   /// Unsets last stat
   fn run(&mut self) {
     self.is_running = true;
     self.stat = None;
+
+    self.run_started_at = Some(Instant::now());
+    self.run_ended_at = None;
   }
 
-  /// Validates an inserted char
+  /// Returns the time elapsed since the start of the run
   ///
-  /// If it is not valid, insert it into the set of mistakes
-  fn validate_input_char(&mut self, inserted_char: char) {
-    if let Some(generated_char) = self.generated_text.chars().nth(self.cursor_index)
-      && generated_char != inserted_char
-    {
-      self
-        .mistake_handler
-        .add_to_mistakes_indexes(self.cursor_index);
-    }
+  /// The stopped run returns its whole time.
+  fn get_elapsed(&self) -> Duration {
+    let Some(run_started_at) = self.run_started_at else {
+      return Duration::ZERO;
+    };
+
+    let now = Instant::now();
+    let run_ended_at = self
+      .run_ended_at
+      .map_or(now, |run_ended_at| run_ended_at.min(now));
+
+    run_ended_at.saturating_duration_since(run_started_at)
   }
 
-  /// Moves the cursor position forward
+  /// Returns the WPM of the running run
+  fn get_live_wpm(&self) -> usize {
+    StatHelper::get_calculated_wpm(
+      &self.typing_input.get_char_counts(),
+      self.get_elapsed().max(LIVE_WPM_MIN_ELAPSED).as_secs_f64(),
+    )
+  }
+
+  /// Returns the accuracy of the running run
   ///
-  /// Also validates a char
-  fn move_cursor_forward_with(&mut self, c: char) {
-    self.validate_input_char(c);
-    self.input.push(c);
-    self.cursor_index += 1;
+  /// 100% before the first typed char.
+  fn get_live_accuracy(&self) -> f64 {
+    if self.typing_input.get_input().is_empty() {
+      return 100.0;
+    }
+
+    StatHelper::get_calculated_chars_accuracy(&self.typing_input.get_char_counts())
   }
 
-  /// Moves the cursor position backward
+  /// Appends more words if the run is close to the end of the text
   ///
-  /// Remove the incorrect symbol from the set if its exists
-  fn move_cursor_backward(&mut self) {
-    if self.input.pop().is_none() {
-      return;
+  /// The zen and timed runs do not end with the text.
+  fn append_words(&mut self) {
+    let remaining_chars = self
+      .typing_input
+      .get_text()
+      .get_graphemes_count()
+      .saturating_sub(self.typing_input.get_cursor_index());
+
+    if remaining_chars < APPEND_REMAINING_CHARS {
+      let words = Generator::generate_random_string(&self.config.borrow());
+      self.typing_input.push_text(&words);
+    }
+  }
+
+  /// Applies the recorded keystroke, as if it was typed
+  pub fn apply_keystroke(&mut self, keystroke: &Keystroke) {
+    match keystroke.get_kind() {
+      KeystrokeKind::Backspace => self.move_cursor_backward(),
+      KeystrokeKind::Correct | KeystrokeKind::Incorrect => {
+        self.move_cursor_forward_with(keystroke.get_typed_char())
+      }
     }
+  }
 
-    self.cursor_index -= 1;
+  /// Remembers the time of the keystroke
+  ///
+  /// Returns the offset of the keystroke (time elapsed since the start of the run).
+  fn record_keystroke_time(&mut self) -> Duration {
+    self.last_keystroke_at = Some(Instant::now());
+
+    self
+      .run_started_at
+      .map(|run_started_at| run_started_at.elapsed())
+      .unwrap_or_default()
+  }
 
-    if self.mistake_handler.is_char_mistaken(self.cursor_index) {
-      self
-        .mistake_handler
-        .remove_from_mistakes_indexes(self.cursor_index);
+  /// Types the newline of the code
+  ///
+  /// With the auto indent, the leading indentation
+  /// of the next line is typed as well.
+  fn type_code_newline(&mut self) {
+    let is_newline_expected = self.get_expected_grapheme() == Some("\n");
+    let has_auto_indent = self.config.borrow().has_auto_indent;
+
+    self.move_cursor_forward_with('\n');
+
+    if is_newline_expected && has_auto_indent {
+      while self.get_expected_grapheme() == Some(" ") {
+        self.move_cursor_forward_with(' ');
+      }
     }
   }
 
-  // Deletes the last word form the input.
-  // Handles trailing spaces and updates mistakes.
-  pub fn delete_last_word(&mut self) {
-    if self.input.is_empty() {
+  /// Types the indentation of the code
+  ///
+  /// Types the expected spaces up to the next indentation level.
+  /// A tab outside of the indentation is a mistake.
+  fn type_code_indent(&mut self) {
+    let text = self.typing_input.get_text();
+    let cursor_byte_index = text.get_byte_index(self.typing_input.get_cursor_index());
+
+    let expected_spaces = text[cursor_byte_index..]
+      .chars()
+      .take_while(|c| *c == ' ')
+      .count();
+
+    if expected_spaces == 0 {
+      self.move_cursor_forward_with('\t');
       return;
     }
 
-    let original_input_len = self.input.len();
+    let line_start = text[..cursor_byte_index]
+      .rfind('\n')
+      .map_or(0, |newline_index| newline_index + 1);
+
+    let column = text[line_start..cursor_byte_index].get_graphemes_count();
 
-    // Find the end of the actual contnet (trim trailing whitespace for logic)
-    let trimmed_end_len = self.input.trim_end().len();
+    let spaces = (INDENT_WIDTH - column % INDENT_WIDTH).min(expected_spaces);
+
+    for _ in 0..spaces {
+      self.move_cursor_forward_with(' ');
+    }
+  }
 
-    if trimmed_end_len == 0 {
-      // Input was all spaces
-      for i in 0..original_input_len {
-        self.mistake_handler.remove_from_mistakes_indexes(i);
+  /// Returns the style of the untyped char of the code
+  ///
+  /// Tokens are colored by the colors of the active layout.
+  fn get_token_style(&self, index: usize, primary_color: Color, text_color: Color) -> Style {
+    match self.token_kinds.get(index) {
+      Some(TokenKind::Keyword) => Style::default().fg(text_color).bold(),
+      Some(TokenKind::String) | Some(TokenKind::Number) => {
+        Style::default().fg(primary_color.to_dark().to_dark())
       }
-      self.input.clear();
-      self.cursor_index = 0;
-      return;
+      Some(TokenKind::Comment) => Style::default().fg(text_color.to_dark()).italic(),
+      Some(TokenKind::Text) | None => Style::default().fg(text_color),
     }
+  }
 
-    // Find the last space before the last word in the trimmed part
-    let last_word_start_idx = match self.input[..trimmed_end_len].rfind(' ') {
-      Some(space_idx) => space_idx + 1, // Word starts after the space
-      None => 0,                        // No space found, word starts at the beginning
-    };
+  /// Returns the grapheme expected at the cursor
+  fn get_expected_grapheme(&self) -> Option<&str> {
+    self
+      .typing_input
+      .get_text()
+      .get_grapheme(self.typing_input.get_cursor_index())
+  }
+
+  /// Moves the cursor position forward
+  ///
+  /// Also validates a grapheme.
+  fn move_cursor_forward_with(&mut self, c: char) {
+    let offset = self.record_keystroke_time();
+    self.typing_input.type_char(c, offset);
+  }
+
+  /// Moves the cursor position backward
+  fn move_cursor_backward(&mut self) {
+    let offset = self.record_keystroke_time();
+    self.typing_input.backspace(offset);
+  }
+
+  /// Deletes the last word from the input.
+  ///
+  /// Trailing whitespaces are deleted with the word.
+  pub fn delete_last_word(&mut self) {
+    let offset = self.record_keystroke_time();
+    self.typing_input.delete_last_word(offset);
+  }
 
-    for i in last_word_start_idx..original_input_len {
-      self.mistake_handler.remove_from_mistakes_indexes(i);
+  /// Returns the ghost cursor position at the elapsed time of the run
+  ///
+  /// None if the ghost is not loaded or the run is not running.
+  fn get_ghost_cursor_index(&self) -> Option<usize> {
+    if !self.is_running {
+      return None;
     }
 
-    self.input.truncate(last_word_start_idx);
-    self.cursor_index = self.input.len();
+    let ghost = self.ghost.as_ref()?;
+    let run_started_at = self.run_started_at?;
+
+    let ghost_text = ghost.get_typed_text_at(run_started_at.elapsed());
+
+    Some(self.typing_input.get_aligned_index(&ghost_text))
   }
 
   /// Returns the raw WPM
@@ -492,6 +1066,14 @@ impl TypingScreen {
     }
   }
 
+  /// Returns the metrics of the run
+  pub fn get_calculated_metrics(&self) -> Option<&RunMetrics> {
+    self
+      .stat
+      .as_ref()
+      .and_then(|last_stat| last_stat.get_metrics())
+  }
+
   /// Returns the true accuracy
   pub fn get_true_calculated_accuracy(&self) -> f64 {
     if let Some(last_stat) = &self.stat {
@@ -503,8 +1085,11 @@ impl TypingScreen {
 
   /// Prepares and returns a paragraph.
   ///
+  /// The text is wrapped into the lines of the `text_width`,
+  /// only the `VIEWPORT_LINES` around the cursor are visible.
+  ///
   /// If popup window is showed then colors converts to dark.
-  pub fn get_paragraph(&self, layout: &TukaiLayout) -> Paragraph<'_> {
+  pub fn get_paragraph(&self, layout: &TukaiLayout, text_width: u16) -> Paragraph<'_> {
     let mut lines = Vec::new();
 
     let (primary_color, error_color, text_color) = {
@@ -523,37 +1108,129 @@ impl TypingScreen {
       }
     };
 
-    let remaining_time_line = Line::from(vec![
-      Span::from(format!("⏳{}", self.get_remaining_time(),))
-        .style(Style::default().fg(primary_color).bold()),
-    ]);
+    let ghost_cursor_index = self.get_ghost_cursor_index();
+
+    // Timed runs count down, others count the elapsed time
+    let time_text = match self.typing_mode {
+      TypingMode::Time => format!("⏳{}", self.get_remaining_time()),
+      TypingMode::Words(words_count) => {
+        let typed_words_count = self
+          .typing_input
+          .get_input()
+          .chars()
+          .filter(|c| *c == ' ')
+          .count();
+        format!(
+          "⏱{}  📝{typed_words_count}/{words_count}",
+          self.get_elapsed().as_secs()
+        )
+      }
+      TypingMode::Zen | TypingMode::Quote | TypingMode::Code => {
+        format!("⏱{}", self.get_elapsed().as_secs())
+      }
+    };
 
-    let text_line = self
-      .generated_text
-      .chars()
-      .enumerate()
-      .map(|(i, c)| {
-        if i == self.cursor_index {
-          Span::from(c.to_string()).style(
-            Style::default()
-              .fg(layout.get_text_current_color())
-              .bg(layout.get_text_current_bg_color()),
-          )
-        } else if i < self.cursor_index {
-          if self.input.chars().nth(i) == Some(c) {
-            Span::from(c.to_string()).style(Style::default().fg(primary_color))
-          } else {
-            Span::from(c.to_string()).style(
-              Style::default()
-                .fg(error_color)
-                .add_modifier(Modifier::CROSSED_OUT),
-            )
-          }
-        } else {
-          Span::from(c.to_string()).style(Style::default().fg(text_color))
-        }
-      })
-      .collect::<Line>();
+    let mut remaining_time_spans =
+      vec![Span::from(time_text).style(Style::default().fg(primary_color).bold())];
+
+    // Count of chars ahead (positive) or behind (negative) the ghost
+    if let Some(ghost_cursor_index) = ghost_cursor_index {
+      let ghost_difference =
+        self.typing_input.get_cursor_index() as isize - ghost_cursor_index as isize;
+
+      remaining_time_spans.push(
+        Span::from(format!("  👻{ghost_difference:+}"))
+          .style(Style::default().fg(primary_color.to_dark())),
+      );
+    }
+
+    if self.is_running {
+      remaining_time_spans.push(
+        Span::from(format!(
+          "  ⚡{} wpm  🎯{:.0}%",
+          self.get_live_wpm(),
+          self.get_live_accuracy()
+        ))
+        .style(Style::default().fg(text_color)),
+      );
+    }
+
+    let remaining_time_line = Line::from(remaining_time_spans);
+
+    let is_code = self.typing_mode == TypingMode::Code;
+
+    // Untyped code is colored by the tokens
+    let get_untyped_style = |index: usize| {
+      if is_code {
+        self.get_token_style(index, primary_color, text_color)
+      } else {
+        Style::default().fg(text_color)
+      }
+    };
+
+    let styled_graphemes =
+      self
+        .typing_input
+        .get_styled_graphemes(layout, primary_color, error_color, get_untyped_style);
+
+    let cursor_index = styled_graphemes
+      .iter()
+      .position(|styled_grapheme| styled_grapheme.is_cursor())
+      .unwrap_or(styled_graphemes.len());
+
+    let graphemes = styled_graphemes
+      .iter()
+      .map(|styled_grapheme| styled_grapheme.get_compared().get_displayed())
+      .collect::<Vec<&str>>();
+
+    let get_grapheme_span = |styled_grapheme: &StyledGrapheme| {
+      let compared_grapheme = styled_grapheme.get_compared();
+
+      // Newlines of the code are visible as a return symbol
+      let displayed_grapheme = match compared_grapheme.get_displayed() {
+        "\n" => "↵",
+        grapheme => grapheme,
+      };
+
+      let span = Span::from(displayed_grapheme.to_string()).style(styled_grapheme.get_style());
+
+      let is_ghost_cursor = compared_grapheme
+        .get_expected_index()
+        .is_some_and(|index| ghost_cursor_index == Some(index));
+
+      // Ghost cursor is drawn under the typing cursor
+      if is_ghost_cursor && !styled_grapheme.is_cursor() {
+        span.patch_style(
+          Style::default()
+            .bg(primary_color.to_dark())
+            .add_modifier(Modifier::UNDERLINED),
+        )
+      } else {
+        span
+      }
+    };
+
+    let wrapped_lines = get_wrapped_lines(&graphemes, text_width);
+
+    // Only the lines around the cursor are visible
+    let cursor_line = wrapped_lines
+      .iter()
+      .position(|line| line.contains(&cursor_index))
+      .unwrap_or(wrapped_lines.len().saturating_sub(1));
+
+    let first_visible_line = cursor_line
+      .saturating_sub(1)
+      .min(wrapped_lines.len().saturating_sub(VIEWPORT_LINES));
+
+    let text_lines = wrapped_lines
+      .into_iter()
+      .skip(first_visible_line)
+      .take(VIEWPORT_LINES)
+      .map(|line| {
+        line
+          .map(|i| get_grapheme_span(&styled_graphemes[i]))
+          .collect::<Line>()
+      });
 
     let empty_line = Line::from(Vec::new());
 
@@ -561,12 +1238,131 @@ impl TypingScreen {
 
     lines.push(empty_line.clone());
 
-    lines.push(text_line);
+    lines.extend(text_lines);
 
     lines.push(empty_line);
 
     let text = Text::from(lines);
 
-    Paragraph::new(text).wrap(Wrap { trim: true })
+    Paragraph::new(text)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::storage::stat_helper::CharCounts;
+  use ratatui::crossterm::event::KeyModifiers;
+
+  /// Typing screen without the stored runs (the storage is not read)
+  fn get_test_typing_screen() -> TypingScreen {
+    let storage_handler = StorageHandler::new("test_typing_screen.tk");
+    TypingScreen::new(
+      Rc::new(RefCell::new(TukaiConfig::default())),
+      &storage_handler,
+    )
+  }
+
+  fn get_line_texts(text: &str, width: u16) -> Vec<String> {
+    let graphemes = text.get_graphemes();
+
+    get_wrapped_lines(&graphemes, width)
+      .into_iter()
+      .map(|line| graphemes[line].concat())
+      .collect()
+  }
+
+  #[test]
+  fn wrap_text_lines() {
+    assert_eq!(
+      get_line_texts("one two three four ", 9),
+      vec!["one two ", "three ", "four "]
+    );
+
+    assert_eq!(get_line_texts("abcdefghij", 5), vec!["abcd", "efgh", "ij"]);
+    assert_eq!(get_line_texts("fn a() {\n    b\n}", 40).len(), 3);
+    assert_eq!(get_line_texts("", 10), vec![""]);
+    assert_eq!(
+      get_line_texts("e\u{301}te\u{301} ab", 4),
+      vec!["e\u{301}te\u{301} ", "ab"]
+    );
+  }
+
+  #[test]
+  fn measure_run_time() {
+    let mut typing_screen = get_test_typing_screen();
+    typing_screen.typing_mode = TypingMode::Time;
+    typing_screen.typing_duration = TypingDuration::ThirtySec;
+
+    let run_started_at = Instant::now()
+      .checked_sub(Duration::from_millis(20_500))
+      .unwrap();
+
+    typing_screen.run_started_at = Some(run_started_at);
+    assert_eq!(typing_screen.get_remaining_time(), 10);
+    assert!(!typing_screen.is_finished());
+
+    typing_screen.typing_duration = TypingDuration::FifteenSec;
+    assert_eq!(typing_screen.get_remaining_time(), 0);
+    assert!(typing_screen.is_finished());
+
+    // 10 words typed in the whole minute
+    typing_screen.typing_input = TypingInput::new("a".repeat(50));
+
+    for _ in 0..50 {
+      typing_screen.typing_input.type_char('a', Duration::ZERO);
+    }
+    typing_screen.run_ended_at = Some(run_started_at + Duration::from_secs(60));
+    assert!(typing_screen.get_elapsed() < Duration::from_secs(60));
+
+    typing_screen.run_started_at = Instant::now().checked_sub(Duration::from_secs(90));
+    typing_screen.run_ended_at = typing_screen
+      .run_started_at
+      .map(|run_started_at| run_started_at + Duration::from_secs(60));
+    assert_eq!(typing_screen.get_elapsed(), Duration::from_secs(60));
+    assert_eq!(typing_screen.get_live_wpm(), 10);
+    assert_eq!(typing_screen.get_live_accuracy(), 100.0);
+  }
+
+  #[test]
+  fn keep_run_start_at_the_text_start() {
+    let mut typing_screen = get_test_typing_screen();
+    let type_key = |typing_screen: &mut TypingScreen, key_code: KeyCode| {
+      typing_screen.handle_events(KeyEvent::new(key_code, KeyModifiers::NONE));
+    };
+
+    type_key(&mut typing_screen, KeyCode::Char('x'));
+
+    let run_started_at = Instant::now().checked_sub(Duration::from_secs(5));
+    typing_screen.run_started_at = run_started_at;
+
+    // Back at the start of the text in the middle of the run
+    type_key(&mut typing_screen, KeyCode::Backspace);
+    assert_eq!(typing_screen.typing_input.get_cursor_index(), 0);
+
+    type_key(&mut typing_screen, KeyCode::Char('x'));
+    assert_eq!(typing_screen.run_started_at, run_started_at);
+    assert!(typing_screen.get_elapsed() >= Duration::from_secs(5));
+
+    // The keystrokes typed before are kept
+    assert_eq!(typing_screen.typing_input.get_keystroke_log().len(), 3);
+  }
+
+  #[test]
+  fn replay_run_text() {
+    let stat = Stat::new(
+      TypingDuration::Minute,
+      Duration::from_secs(60),
+      CharCounts::default(),
+      0,
+    )
+    .text("kaz\u{301}dy ");
+
+    let typing_screen =
+      TypingScreen::new_replay(Rc::new(RefCell::new(TukaiConfig::default())), &stat);
+
+    // The combining accent is kept (composed)
+    assert_eq!(typing_screen.typing_input.get_text(), "kaźdy ");
+    assert!(typing_screen.ghost.is_none());
   }
 }
@@ -0,0 +1,448 @@
+use std::time::Duration;
+
+use ratatui::style::{Color, Modifier, Style};
+use unicode_normalization::UnicodeNormalization;
+
+use crate::{
+  config::TukaiLayout,
+  graphemes::GraphemeText,
+  screens::ToDark,
+  storage::{
+    keystroke::{KeystrokeKind, KeystrokeLog},
+    stat_helper::{CharCounts, CharKind, ComparedGrapheme, StatHelper},
+  },
+};
+
+/// Grapheme of the aligned input with its style
+pub struct StyledGrapheme<'a> {
+  // Typed (or expected) grapheme aligned with the text
+  compared: ComparedGrapheme<'a>,
+
+  style: Style,
+
+  // The cursor is on the grapheme
+  is_cursor: bool,
+}
+
+impl<'a> StyledGrapheme<'a> {
+  pub fn get_compared(&self) -> &ComparedGrapheme<'a> {
+    &self.compared
+  }
+
+  pub fn get_style(&self) -> Style {
+    self.style
+  }
+
+  pub fn is_cursor(&self) -> bool {
+    self.is_cursor
+  }
+}
+
+/// Text typed by the user, shared by the typing screens
+///
+/// Aligns the input with the text word by word (see `StatHelper::compare_texts`),
+/// the same way as the run is scored, and records the keystrokes.
+///
+/// Both the text and the input are kept in the NFC,
+/// so a letter typed as a base char and a combining accent (i.e. by a dead key)
+/// matches the precomposed letter of the text.
+pub struct TypingInput {
+  // Text to type
+  text: String,
+
+  // Typed input
+  input: String,
+
+  // Position of the cursor within the text (in graphemes)
+  cursor_index: usize,
+
+  // Keystrokes typed into the input
+  keystroke_log: KeystrokeLog,
+}
+
+impl TypingInput {
+  pub fn new<S: AsRef<str>>(text: S) -> Self {
+    Self {
+      text: text.as_ref().nfc().collect(),
+      input: String::new(),
+      cursor_index: 0,
+      keystroke_log: KeystrokeLog::new(),
+    }
+  }
+
+  pub fn get_text(&self) -> &str {
+    &self.text
+  }
+
+  pub fn get_input(&self) -> &str {
+    &self.input
+  }
+
+  pub fn get_cursor_index(&self) -> usize {
+    self.cursor_index
+  }
+
+  #[cfg(test)]
+  pub fn get_keystroke_log(&self) -> &KeystrokeLog {
+    &self.keystroke_log
+  }
+
+  /// Takes the recorded keystrokes, the log is left empty
+  pub fn take_keystroke_log(&mut self) -> KeystrokeLog {
+    std::mem::take(&mut self.keystroke_log)
+  }
+
+  /// Appends more text to type
+  pub fn push_text(&mut self, text: &str) {
+    self.text.extend(text.nfc());
+  }
+
+  /// Returns the input aligned with the text
+  pub fn get_compared(&self) -> Vec<ComparedGrapheme<'_>> {
+    StatHelper::compare_texts(&self.text, &self.input)
+  }
+
+  /// Returns the input aligned with the text, styled by the kinds of the graphemes
+  ///
+  /// The cursor is on the first untyped grapheme.
+  /// Other untyped graphemes get the style of their position within the text.
+  pub fn get_styled_graphemes(
+    &self,
+    layout: &TukaiLayout,
+    primary_color: Color,
+    error_color: Color,
+    get_untyped_style: impl Fn(usize) -> Style,
+  ) -> Vec<StyledGrapheme<'_>> {
+    let compared = self.get_compared();
+
+    let cursor_index = compared
+      .iter()
+      .position(|compared_grapheme| compared_grapheme.get_kind() == CharKind::Untyped);
+
+    compared
+      .into_iter()
+      .enumerate()
+      .map(|(i, compared_grapheme)| {
+        let is_cursor = cursor_index == Some(i);
+
+        let style = match compared_grapheme.get_kind() {
+          _ if is_cursor => Style::default()
+            .fg(layout.get_text_current_color())
+            .bg(layout.get_text_current_bg_color()),
+          CharKind::Correct => Style::default().fg(primary_color),
+          CharKind::Incorrect => Style::default()
+            .fg(error_color)
+            .add_modifier(Modifier::CROSSED_OUT),
+          CharKind::Extra => Style::default().fg(error_color),
+          CharKind::Missed => Style::default()
+            .fg(error_color.to_dark())
+            .add_modifier(Modifier::UNDERLINED),
+          CharKind::Untyped => {
+            get_untyped_style(compared_grapheme.get_expected_index().unwrap_or_default())
+          }
+        };
+
+        StyledGrapheme {
+          compared: compared_grapheme,
+          style,
+          is_cursor,
+        }
+      })
+      .collect()
+  }
+
+  /// Returns the position within the text reached by the typed text
+  ///
+  /// The position of the first untyped grapheme, the end of the text if all were typed.
+  pub fn get_aligned_index(&self, typed_text: &str) -> usize {
+    StatHelper::compare_texts(&self.text, typed_text)
+      .iter()
+      .find(|compared_grapheme| compared_grapheme.get_kind() == CharKind::Untyped)
+      .and_then(|compared_grapheme| compared_grapheme.get_expected_index())
+      .unwrap_or_else(|| self.text.get_graphemes_count())
+  }
+
+  /// Returns the counts of the typed chars
+  pub fn get_char_counts(&self) -> CharCounts {
+    CharCounts::from_compared(&self.get_compared())
+  }
+
+  /// Returns the count of the mistakes corrected during the run
+  pub fn get_corrected_errors_count(&self) -> usize {
+    self.keystroke_log.get_errors_counts().0
+  }
+
+  /// Types the char typed at the offset (time elapsed since the start of the run)
+  ///
+  /// Compares the typed grapheme with the text and moves the cursor forward.
+  /// A whitespace typed within a word finishes the word, the cursor jumps to the next word.
+  ///
+  /// A char completing the last typed grapheme (i.e. a combining accent of a dead key)
+  /// keeps the cursor, the completed grapheme is compared again.
+  /// The char is not recorded as a keystroke of its own, it is merged
+  /// into the keystroke of the completed grapheme instead.
+  pub fn type_char(&mut self, c: char, offset: Duration) {
+    if self.input.is_extended_by(c) {
+      self.complete_last_grapheme(c);
+      return;
+    }
+
+    self.input.push(c);
+
+    let (kind, expected_char) = self.get_last_typed_keystroke();
+
+    self.keystroke_log.push(offset, kind, c, expected_char);
+    self.update_cursor_index();
+  }
+
+  /// Removes the last typed grapheme
+  pub fn backspace(&mut self, offset: Duration) {
+    if let Some(index) = self.input.get_graphemes_count().checked_sub(1) {
+      self.remove_graphemes_from(index, offset);
+    }
+  }
+
+  /// Deletes the last word from the input
+  ///
+  /// Trailing whitespaces are deleted with the word.
+  /// A backspace is recorded for every removed grapheme.
+  pub fn delete_last_word(&mut self, offset: Duration) {
+    self.remove_graphemes_from(self.input.get_last_word_start(), offset);
+  }
+
+  /// Moves the cursor to the first untyped grapheme of the text
+  fn update_cursor_index(&mut self) {
+    self.cursor_index = self.get_aligned_index(&self.input);
+  }
+
+  /// Returns the keystroke kind and the expected char of the last typed grapheme
+  ///
+  /// An extra grapheme is an incorrect keystroke without the expected char.
+  fn get_last_typed_keystroke(&self) -> (KeystrokeKind, Option<char>) {
+    let compared = self.get_compared();
+
+    let Some(compared_grapheme) = compared
+      .iter()
+      .rev()
+      .find(|compared_grapheme| compared_grapheme.get_typed().is_some())
+    else {
+      return (KeystrokeKind::Incorrect, None);
+    };
+
+    let kind = if compared_grapheme.get_kind() == CharKind::Correct {
+      KeystrokeKind::Correct
+    } else {
+      KeystrokeKind::Incorrect
+    };
+
+    let expected_char = compared_grapheme
+      .get_expected()
+      .and_then(|grapheme| grapheme.chars().next());
+
+    (kind, expected_char)
+  }
+
+  /// Completes the last typed grapheme by the char
+  ///
+  /// The completed grapheme is composed (NFC) and compared again.
+  /// Its keystroke gets the composed char, if the grapheme is a single char.
+  fn complete_last_grapheme(&mut self, c: char) {
+    let index = self.input.get_graphemes_count() - 1;
+    let byte_index = self.input.get_byte_index(index);
+
+    let mut grapheme = self.input.split_off(byte_index);
+    grapheme.push(c);
+    self.input.extend(grapheme.nfc());
+
+    let (kind, _) = self.get_last_typed_keystroke();
+
+    let mut chars = self.input[byte_index..].chars();
+
+    let typed_char = match (chars.next(), chars.next()) {
+      (Some(composed_char), None) => Some(composed_char),
+      _ => None,
+    };
+
+    self.keystroke_log.complete_last_typed(kind, typed_char);
+    self.update_cursor_index();
+  }
+
+  /// Removes the typed graphemes from the position (within the input),
+  /// records a backspace for every removed grapheme.
+  fn remove_graphemes_from(&mut self, index: usize, offset: Duration) {
+    // The typed graphemes keep their order in the compared text
+    let removed_keystrokes = self
+      .get_compared()
+      .into_iter()
+      .filter_map(|compared_grapheme| {
+        let removed_char = compared_grapheme.get_typed()?.chars().next()?;
+        let expected_char = compared_grapheme
+          .get_expected()
+          .and_then(|grapheme| grapheme.chars().next());
+
+        Some((removed_char, expected_char))
+      })
+      .skip(index)
+      .collect::<Vec<(char, Option<char>)>>();
+
+    for (removed_char, expected_char) in removed_keystrokes.into_iter().rev() {
+      self.keystroke_log.push(
+        offset,
+        KeystrokeKind::Backspace,
+        removed_char,
+        expected_char,
+      );
+    }
+
+    self.input.truncate(self.input.get_byte_index(index));
+    self.update_cursor_index();
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::storage::wpm_timeline::WpmTimeline;
+
+  fn type_text(typing_input: &mut TypingInput, text: &str) {
+    for c in text.chars() {
+      typing_input.type_char(c, Duration::ZERO);
+    }
+  }
+
+  #[test]
+  fn type_accented_text() {
+    let mut typing_input = TypingInput::new("zażółć gęślą jaźń ");
+
+    type_text(&mut typing_input, "zażółć gęśla");
+    assert_eq!(typing_input.get_cursor_index(), 12);
+    assert_eq!(typing_input.get_char_counts().get_incorrect(), 1);
+
+    typing_input.delete_last_word(Duration::ZERO);
+    assert_eq!(typing_input.get_cursor_index(), 7);
+    assert_eq!(typing_input.get_input(), "zażółć ");
+    assert_eq!(typing_input.get_char_counts().get_errors_count(), 0);
+
+    // Accent of a dead key sent as a combining char
+    type_text(&mut typing_input, "gęśla\u{328}");
+    assert_eq!(typing_input.get_cursor_index(), 12);
+    assert_eq!(typing_input.get_input(), "zażółć gęślą");
+    assert_eq!(typing_input.get_char_counts().get_errors_count(), 0);
+
+    // The accent is merged into the keystroke of the letter
+    let keystrokes = typing_input.get_keystroke_log().get_keystrokes();
+    let last_keystroke = keystrokes.last().unwrap();
+    assert_eq!(last_keystroke.get_kind(), KeystrokeKind::Correct);
+    assert_eq!(last_keystroke.get_typed_char(), 'ą');
+    assert_eq!(last_keystroke.get_expected_char(), Some('ą'));
+  }
+
+  #[test]
+  fn type_decomposed_text() {
+    let mut typing_input = TypingInput::new("kaz\u{301}dy ");
+    assert_eq!(typing_input.get_text(), "kaźdy ");
+
+    type_text(&mut typing_input, "kaźdy ");
+    assert_eq!(typing_input.get_char_counts().get_errors_count(), 0);
+  }
+
+  #[test]
+  fn type_by_words() {
+    let mut typing_input = TypingInput::new("abc de fg ");
+
+    // The space skips the rest of the word
+    type_text(&mut typing_input, "a ");
+    assert_eq!(typing_input.get_cursor_index(), 4);
+
+    // The extra chars keep the cursor at the end of the word
+    type_text(&mut typing_input, "dexx");
+    assert_eq!(typing_input.get_cursor_index(), 6);
+
+    let char_counts = typing_input.get_char_counts();
+    assert_eq!(char_counts.get_correct(), 4);
+    assert_eq!(char_counts.get_extra(), 2);
+    assert_eq!(char_counts.get_missed(), 2);
+
+    let kinds = typing_input
+      .get_keystroke_log()
+      .get_keystrokes()
+      .iter()
+      .map(|keystroke| keystroke.get_kind())
+      .collect::<Vec<KeystrokeKind>>();
+
+    assert_eq!(
+      kinds,
+      [
+        KeystrokeKind::Correct,
+        KeystrokeKind::Correct,
+        KeystrokeKind::Correct,
+        KeystrokeKind::Correct,
+        KeystrokeKind::Incorrect,
+        KeystrokeKind::Incorrect,
+      ]
+    );
+
+    // The extra chars are removed first
+    typing_input.backspace(Duration::ZERO);
+    typing_input.backspace(Duration::ZERO);
+    typing_input.backspace(Duration::ZERO);
+    assert_eq!(typing_input.get_cursor_index(), 5);
+
+    let last_keystroke = typing_input
+      .get_keystroke_log()
+      .get_keystrokes()
+      .last()
+      .unwrap();
+    assert_eq!(last_keystroke.get_kind(), KeystrokeKind::Backspace);
+    assert_eq!(last_keystroke.get_expected_char(), Some('e'));
+  }
+
+  #[test]
+  fn type_past_the_end_of_text() {
+    let mut typing_input = TypingInput::new("ab ");
+
+    type_text(&mut typing_input, "ab cd");
+    assert_eq!(typing_input.get_cursor_index(), 3);
+    assert_eq!(typing_input.get_char_counts().get_extra(), 2);
+
+    let last_keystroke = typing_input
+      .get_keystroke_log()
+      .get_keystrokes()
+      .last()
+      .unwrap();
+    assert_eq!(last_keystroke.get_kind(), KeystrokeKind::Incorrect);
+    assert_eq!(last_keystroke.get_expected_char(), None);
+  }
+
+  #[test]
+  fn match_timeline_with_char_counts() {
+    let mut typing_input = TypingInput::new("abc de fg ");
+
+    for (i, c) in "axc d".chars().enumerate() {
+      typing_input.type_char(c, Duration::from_millis(i as u64 * 300));
+    }
+
+    // The correct chars are removed with the mistake
+    for i in 0..4 {
+      typing_input.backspace(Duration::from_millis(1600 + i * 100));
+    }
+
+    type_text(&mut typing_input, "bc de fgh");
+
+    let char_counts = typing_input.get_char_counts();
+    let elapsed = Duration::from_secs(3);
+
+    let samples = WpmTimeline::from(typing_input.get_keystroke_log()).get_samples(elapsed);
+    let last_sample = samples.last().unwrap();
+
+    assert_eq!(
+      last_sample.get_wpm().round() as usize,
+      StatHelper::get_calculated_wpm(&char_counts, elapsed.as_secs_f64())
+    );
+    assert_eq!(
+      last_sample.get_wpm(),
+      char_counts.get_correct() as f64 / 5.0 * 20.0
+    );
+    assert_eq!(typing_input.get_corrected_errors_count(), 1);
+  }
+}
@@ -0,0 +1,320 @@
+use std::path::Path;
+
+use rust_embed::RustEmbed;
+
+use crate::graphemes::GraphemeText;
+
+#[derive(RustEmbed)]
+#[folder = "snippets/"]
+struct SnippetDictionary;
+
+/// Count of spaces a tab is expanded to
+pub const INDENT_WIDTH: usize = 4;
+
+/// Kind of a token, used for the syntax coloring
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+  Text,
+  Keyword,
+  String,
+  Number,
+  Comment,
+}
+
+/// Basic syntax of a programming language
+struct Syntax {
+  // Reserved words of the language
+  keywords: &'static [&'static str],
+
+  // Prefix of the line comment
+  comment: &'static str,
+
+  // Chars quoting the string literals
+  quotes: &'static [char],
+}
+
+impl Syntax {
+  /// Returns the syntax of the language
+  ///
+  /// Unknown languages use the C-like comments and strings without keywords.
+  fn from_lang(lang: &str) -> Self {
+    match lang {
+      "rust" | "rs" => Syntax {
+        keywords: &[
+          "as", "break", "const", "continue", "crate", "else", "enum", "false", "fn", "for", "if",
+          "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref", "return",
+          "self", "Self", "static", "struct", "trait", "true", "type", "use", "where", "while",
+        ],
+        comment: "//",
+        quotes: &['"'],
+      },
+      "python" | "py" => Syntax {
+        keywords: &[
+          "and", "as", "class", "def", "elif", "else", "except", "False", "for", "from", "if",
+          "import", "in", "is", "lambda", "None", "not", "or", "pass", "raise", "return", "True",
+          "try", "while", "with", "yield",
+        ],
+        comment: "#",
+        quotes: &['"', '\''],
+      },
+      "go" => Syntax {
+        keywords: &[
+          "break",
+          "case",
+          "const",
+          "continue",
+          "defer",
+          "else",
+          "false",
+          "for",
+          "func",
+          "go",
+          "if",
+          "import",
+          "interface",
+          "map",
+          "nil",
+          "package",
+          "range",
+          "return",
+          "struct",
+          "switch",
+          "true",
+          "type",
+          "var",
+        ],
+        comment: "//",
+        quotes: &['"', '`'],
+      },
+      _ => Syntax {
+        keywords: &[],
+        comment: "//",
+        quotes: &['"'],
+      },
+    }
+  }
+}
+
+/// Code snippet of the code mode
+#[derive(Debug, Clone, PartialEq)]
+pub struct Snippet {
+  // Source code (indented by spaces, without trailing whitespaces)
+  code: String,
+
+  // Programming language (name of the snippet directory)
+  lang: String,
+
+  // File name of the snippet
+  name: String,
+}
+
+impl Snippet {
+  /// Creates a snippet from the source code
+  ///
+  /// Tabs are expanded to the `INDENT_WIDTH` spaces,
+  /// trailing whitespaces of the lines and the code are removed.
+  pub fn new<S: Into<String>>(lang: S, name: S, code: &str) -> Self {
+    let code = code
+      .lines()
+      .map(|line| line.replace('\t', &" ".repeat(INDENT_WIDTH)))
+      .map(|line| line.trim_end().to_string())
+      .collect::<Vec<String>>()
+      .join("\n");
+
+    Self {
+      code: code.trim_matches('\n').to_string(),
+      lang: lang.into(),
+      name: name.into(),
+    }
+  }
+
+  /// Loads the snippets embedded from the `snippets` folder
+  /// and from the user's snippets directory.
+  ///
+  /// Snippets are grouped by the language directories (i.e. `rust/fibonacci.rs`).
+  /// A custom snippet with the same language and name replaces the embedded one.
+  pub fn load_snippets(dir_path: &Path) -> Vec<Snippet> {
+    let mut snippets = SnippetDictionary::iter()
+      .filter_map(|file_path| {
+        let (lang, name) = file_path.split_once('/')?;
+        let file = SnippetDictionary::get(&file_path)?;
+        let code = String::from_utf8(file.data.into_owned()).ok()?;
+
+        Some(Snippet::new(lang, name, &code))
+      })
+      .collect::<Vec<Snippet>>();
+
+    for custom_snippet in Snippet::load_custom_snippets(dir_path) {
+      snippets.retain(|snippet| {
+        snippet.lang != custom_snippet.lang || snippet.name != custom_snippet.name
+      });
+
+      snippets.push(custom_snippet);
+    }
+
+    snippets
+  }
+
+  /// Returns the snippets from the language directories of the user's snippets directory.
+  ///
+  /// If the directory does not exist, returns an empty vector.
+  fn load_custom_snippets(dir_path: &Path) -> Vec<Snippet> {
+    let Ok(lang_entries) = std::fs::read_dir(dir_path) else {
+      return Vec::new();
+    };
+
+    let mut snippets = Vec::new();
+
+    for lang_path in lang_entries.flatten().map(|entry| entry.path()) {
+      let Some(lang) = lang_path.file_name().and_then(|lang| lang.to_str()) else {
+        continue;
+      };
+
+      let Ok(entries) = std::fs::read_dir(&lang_path) else {
+        continue;
+      };
+
+      for path in entries.flatten().map(|entry| entry.path()) {
+        let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+          continue;
+        };
+
+        if let Ok(code) = std::fs::read_to_string(&path) {
+          snippets.push(Snippet::new(lang, name, &code));
+        }
+      }
+    }
+
+    snippets.sort_by(|a, b| (&a.lang, &a.name).cmp(&(&b.lang, &b.name)));
+    snippets
+  }
+
+  /// Returns the kind of the token for each grapheme of the code
+  ///
+  /// Recognizes the keywords, string literals, numbers and line comments.
+  /// Graphemes are classified by their first char.
+  pub fn get_token_kinds(&self) -> Vec<TokenKind> {
+    let syntax = Syntax::from_lang(&self.lang);
+
+    let chars = self
+      .code
+      .get_graphemes()
+      .into_iter()
+      .map(|grapheme| grapheme.chars().next().unwrap_or(' '))
+      .collect::<Vec<char>>();
+    let comment = syntax.comment.chars().collect::<Vec<char>>();
+
+    let mut token_kinds = vec![TokenKind::Text; chars.len()];
+    let mut i = 0;
+
+    while i < chars.len() {
+      let c = chars[i];
+      let start = i;
+
+      let token_kind = if chars[i..].starts_with(&comment) {
+        while i < chars.len() && chars[i] != '\n' {
+          i += 1;
+        }
+
+        TokenKind::Comment
+      } else if syntax.quotes.contains(&c) {
+        i += 1;
+
+        // Strings are closed by the same quote or at the end of the line
+        while i < chars.len() && chars[i] != c && chars[i] != '\n' {
+          i += if chars[i] == '\\' { 2 } else { 1 };
+        }
+
+        i = (i + 1).min(chars.len());
+        TokenKind::String
+      } else if c.is_alphanumeric() || c == '_' {
+        while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+          i += 1;
+        }
+
+        let word = chars[start..i].iter().collect::<String>();
+
+        if c.is_ascii_digit() {
+          TokenKind::Number
+        } else if syntax.keywords.contains(&word.as_str()) {
+          TokenKind::Keyword
+        } else {
+          TokenKind::Text
+        }
+      } else {
+        i += 1;
+        TokenKind::Text
+      };
+
+      token_kinds[start..i].fill(token_kind);
+    }
+
+    token_kinds
+  }
+
+  pub fn get_code(&self) -> &str {
+    &self.code
+  }
+
+  pub fn get_lang(&self) -> &str {
+    &self.lang
+  }
+
+  pub fn get_name(&self) -> &str {
+    &self.name
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use uuid::Uuid;
+
+  #[test]
+  fn normalize_code() {
+    let snippet = Snippet::new("go", "main.go", "\nfunc main() {\n\tx := 1  \n}\n\n");
+    assert_eq!(snippet.get_code(), "func main() {\n    x := 1\n}");
+  }
+
+  #[test]
+  fn tokenize_code() {
+    let snippet = Snippet::new("rust", "main.rs", "let s = \"a\\\"b\"; // 42\nx = 42;");
+    let token_kinds = snippet.get_token_kinds();
+
+    let get_kind = |pattern: &str| {
+      let byte_index = snippet.get_code().find(pattern).unwrap();
+      token_kinds[snippet.get_code()[..byte_index].get_graphemes_count()]
+    };
+
+    assert_eq!(get_kind("let"), TokenKind::Keyword);
+    assert_eq!(get_kind(" s"), TokenKind::Text);
+    assert_eq!(get_kind("b\""), TokenKind::String);
+    assert_eq!(get_kind("; //"), TokenKind::Text);
+    assert_eq!(get_kind("// 42"), TokenKind::Comment);
+    assert_eq!(token_kinds[token_kinds.len() - 3], TokenKind::Number);
+    assert_eq!(token_kinds.len(), snippet.get_code().get_graphemes_count());
+  }
+
+  #[test]
+  fn load_custom_snippets() {
+    let dir_path = std::env::temp_dir().join(format!("tukai-snippets-{}", Uuid::new_v4()));
+    std::fs::create_dir_all(dir_path.join("rust")).unwrap();
+    std::fs::write(dir_path.join("rust").join("point.rs"), "struct Point;").unwrap();
+
+    let snippets = Snippet::load_snippets(&dir_path);
+
+    for lang in ["rust", "python", "go"] {
+      assert!(snippets.iter().any(|snippet| snippet.get_lang() == lang));
+    }
+
+    let points = snippets
+      .iter()
+      .filter(|snippet| snippet.get_name() == "point.rs")
+      .collect::<Vec<&Snippet>>();
+
+    assert_eq!(points.len(), 1);
+    assert_eq!(points[0].get_code(), "struct Point;");
+
+    std::fs::remove_dir_all(dir_path).unwrap();
+  }
+}
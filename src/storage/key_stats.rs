@@ -0,0 +1,358 @@
+use std::collections::HashMap;
+
+use super::{
+  keystroke::{KeystrokeKind, KeystrokeLog},
+  stats::Stat,
+};
+
+/// Longest delay counted into the key latency (ms)
+///
+/// Longer delays are pauses rather than the time to find the key.
+const MAX_LATENCY_MS: u32 = 2000;
+
+/// Count of the hits from which the key data are fully trusted
+///
+/// Weakness of the rarely typed keys is scaled down.
+const CONFIDENT_HITS: usize = 10;
+
+/// Weight of the error rate in the weakness
+///
+/// A key mistaken every time is as weak as a key typed 4x slower than average.
+const ERROR_RATE_WEIGHT: f64 = 3.0;
+
+/// Typing data of a single key
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct KeyStat {
+  // Count of the typed chars expected at the key
+  hits: usize,
+
+  // Count of the incorrectly typed chars expected at the key
+  mistakes: usize,
+
+  // Sum of the delays before the key was typed (ms)
+  latency_sum_ms: u64,
+
+  // Count of the delays in the sum
+  latency_count: usize,
+}
+
+impl KeyStat {
+  /// Returns the ratio of the mistakes (0.0 - 1.0)
+  pub fn get_error_rate(&self) -> f64 {
+    if self.hits == 0 {
+      return 0.0;
+    }
+
+    self.mistakes as f64 / self.hits as f64
+  }
+
+  /// Returns the average delay before the key was typed (ms)
+  pub fn get_average_latency_ms(&self) -> Option<u64> {
+    if self.latency_count == 0 {
+      return None;
+    }
+
+    Some(self.latency_sum_ms / self.latency_count as u64)
+  }
+
+  fn add_keystroke(&mut self, kind: KeystrokeKind, latency_ms: Option<u32>) {
+    self.hits += 1;
+
+    if kind == KeystrokeKind::Incorrect {
+      self.mistakes += 1;
+    }
+
+    if let Some(latency_ms) = latency_ms.filter(|latency_ms| *latency_ms <= MAX_LATENCY_MS) {
+      self.latency_sum_ms += latency_ms as u64;
+      self.latency_count += 1;
+    }
+  }
+}
+
+/// Per-key typing data aggregated from the keystroke logs
+///
+/// Keys are the expected chars in lowercase,
+/// backspaces and chars typed past the end of the text are skipped.
+///
+/// Bigrams are the transitions between two consecutively typed keys,
+/// a backspace breaks the transition.
+#[derive(Debug, Default, Clone)]
+pub struct KeyStats {
+  keys: HashMap<char, KeyStat>,
+  bigrams: HashMap<(char, char), KeyStat>,
+}
+
+impl KeyStats {
+  /// Aggregates the keystrokes of all runs
+  pub fn from_stats(stats: &[Stat]) -> Self {
+    let mut key_stats = KeyStats::default();
+
+    for stat in stats {
+      key_stats.add_keystroke_log(stat.get_keystroke_log());
+    }
+
+    key_stats
+  }
+
+  /// Adds the keystrokes of a single run
+  pub fn add_keystroke_log(&mut self, keystroke_log: &KeystrokeLog) {
+    let mut last_offset_ms = None;
+    let mut last_key = None;
+
+    for keystroke in keystroke_log.get_keystrokes() {
+      let latency_ms =
+        last_offset_ms.map(|last_offset_ms| keystroke.get_offset_ms() - last_offset_ms);
+      last_offset_ms = Some(keystroke.get_offset_ms());
+
+      if keystroke.get_kind() == KeystrokeKind::Backspace {
+        last_key = None;
+        continue;
+      }
+
+      let Some(expected_char) = keystroke.get_expected_char() else {
+        last_key = None;
+        continue;
+      };
+
+      let key = get_key(expected_char);
+
+      self
+        .keys
+        .entry(key)
+        .or_default()
+        .add_keystroke(keystroke.get_kind(), latency_ms);
+
+      if let Some(last_key) = last_key {
+        self
+          .bigrams
+          .entry((last_key, key))
+          .or_default()
+          .add_keystroke(keystroke.get_kind(), latency_ms);
+      }
+
+      last_key = Some(key);
+    }
+  }
+
+  /// Returns the data of the key (case insensitive)
+  pub fn get_key_stat(&self, c: char) -> Option<&KeyStat> {
+    self.keys.get(&get_key(c))
+  }
+
+  /// Returns the keys sorted by the error rate, worst first
+  ///
+  /// Keys without mistakes are skipped.
+  pub fn get_worst_keys(&self) -> Vec<(char, KeyStat)> {
+    let mut keys = self
+      .keys
+      .iter()
+      .filter(|(_, key_stat)| key_stat.mistakes > 0)
+      .map(|(c, key_stat)| (*c, *key_stat))
+      .collect::<Vec<(char, KeyStat)>>();
+
+    keys.sort_by(|(a_char, a), (b_char, b)| {
+      b.get_error_rate()
+        .total_cmp(&a.get_error_rate())
+        .then(a_char.cmp(b_char))
+    });
+
+    keys
+  }
+
+  /// Returns the keys sorted by the average latency, slowest first
+  pub fn get_slowest_keys(&self) -> Vec<(char, KeyStat)> {
+    let mut keys = self
+      .keys
+      .iter()
+      .filter(|(_, key_stat)| key_stat.latency_count > 0)
+      .map(|(c, key_stat)| (*c, *key_stat))
+      .collect::<Vec<(char, KeyStat)>>();
+
+    keys.sort_by(|(a_char, a), (b_char, b)| {
+      b.get_average_latency_ms()
+        .cmp(&a.get_average_latency_ms())
+        .then(a_char.cmp(b_char))
+    });
+
+    keys
+  }
+
+  /// Returns the weight of the word for the adaptive practice
+  ///
+  /// Starts at 1.0 and grows with the weakness
+  /// of every key and bigram of the word.
+  pub fn get_word_weight(&self, word: &str) -> f64 {
+    let average_latency_ms = self.get_average_latency_ms();
+
+    let keys = word.chars().map(get_key).collect::<Vec<char>>();
+
+    let keys_weakness = keys
+      .iter()
+      .filter_map(|key| self.keys.get(key))
+      .map(|key_stat| get_weakness(key_stat, average_latency_ms))
+      .sum::<f64>();
+
+    let bigrams_weakness = keys
+      .windows(2)
+      .filter_map(|bigram| self.bigrams.get(&(bigram[0], bigram[1])))
+      .map(|key_stat| get_weakness(key_stat, average_latency_ms))
+      .sum::<f64>();
+
+    1.0 + keys_weakness + bigrams_weakness
+  }
+
+  /// Returns the weakest letter keys and bigrams, weakest first
+  ///
+  /// Used to show the practice target.
+  pub fn get_weakest(&self, count: usize) -> Vec<String> {
+    let average_latency_ms = self.get_average_latency_ms();
+
+    let keys = self
+      .keys
+      .iter()
+      .filter(|(key, _)| key.is_alphabetic())
+      .map(|(key, key_stat)| (key.to_string(), key_stat));
+
+    let bigrams = self
+      .bigrams
+      .iter()
+      .filter(|((first, second), _)| first.is_alphabetic() && second.is_alphabetic())
+      .map(|((first, second), key_stat)| (format!("{first}{second}"), key_stat));
+
+    let mut weakest = keys
+      .chain(bigrams)
+      .map(|(key, key_stat)| (key, get_weakness(key_stat, average_latency_ms)))
+      .filter(|(_, weakness)| *weakness > 0.0)
+      .collect::<Vec<(String, f64)>>();
+
+    weakest.sort_by(|(a_key, a), (b_key, b)| b.total_cmp(a).then(a_key.cmp(b_key)));
+
+    weakest
+      .into_iter()
+      .take(count)
+      .map(|(key, _)| key)
+      .collect()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.keys.is_empty()
+  }
+
+  /// Returns the average delay before any key was typed (ms)
+  fn get_average_latency_ms(&self) -> Option<f64> {
+    let (latency_sum_ms, latency_count) =
+      self.keys.values().fold((0, 0), |(sum, count), key_stat| {
+        (
+          sum + key_stat.latency_sum_ms,
+          count + key_stat.latency_count,
+        )
+      });
+
+    if latency_count == 0 {
+      return None;
+    }
+
+    Some(latency_sum_ms as f64 / latency_count as f64)
+  }
+}
+
+fn get_key(c: char) -> char {
+  c.to_lowercase().next().unwrap_or(c)
+}
+
+/// Returns how weak the key is (0.0 for a fine key)
+///
+/// Sums the weighted error rate and the latency above the average,
+/// scaled down for the rarely typed keys.
+fn get_weakness(key_stat: &KeyStat, average_latency_ms: Option<f64>) -> f64 {
+  let slowness = match (key_stat.get_average_latency_ms(), average_latency_ms) {
+    (Some(latency_ms), Some(average_latency_ms)) if average_latency_ms > 0.0 => {
+      (latency_ms as f64 / average_latency_ms - 1.0).max(0.0)
+    }
+    _ => 0.0,
+  };
+
+  let confidence = key_stat.hits.min(CONFIDENT_HITS) as f64 / CONFIDENT_HITS as f64;
+
+  (key_stat.get_error_rate() * ERROR_RATE_WEIGHT + slowness) * confidence
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::time::Duration;
+
+  fn get_test_keystroke_log() -> KeystrokeLog {
+    let mut keystroke_log = KeystrokeLog::new();
+
+    for (offset_ms, kind, typed_char, expected_char) in [
+      (0, KeystrokeKind::Correct, 'a', 'a'),
+      (200, KeystrokeKind::Incorrect, 'x', 'b'),
+      (300, KeystrokeKind::Backspace, 'x', 'b'),
+      (500, KeystrokeKind::Correct, 'b', 'b'),
+      (5000, KeystrokeKind::Correct, 'A', 'A'),
+    ] {
+      keystroke_log.push(
+        Duration::from_millis(offset_ms),
+        kind,
+        typed_char,
+        Some(expected_char),
+      );
+    }
+
+    keystroke_log
+  }
+
+  #[test]
+  fn aggregate_keystrokes() {
+    let mut key_stats = KeyStats::default();
+    key_stats.add_keystroke_log(&get_test_keystroke_log());
+
+    let b_stat = key_stats.get_key_stat('b').unwrap();
+    assert_eq!(b_stat.hits, 2);
+    assert_eq!(b_stat.get_error_rate(), 0.5);
+    assert_eq!(b_stat.get_average_latency_ms(), Some(200));
+
+    // Pause before the uppercase `A` is not counted
+    let a_stat = key_stats.get_key_stat('a').unwrap();
+    assert_eq!(a_stat.hits, 2);
+    assert_eq!(a_stat.get_average_latency_ms(), None);
+  }
+
+  #[test]
+  fn sort_worst_keys() {
+    let mut key_stats = KeyStats::default();
+    key_stats.add_keystroke_log(&get_test_keystroke_log());
+
+    let worst_keys = key_stats.get_worst_keys();
+    assert_eq!(worst_keys.len(), 1);
+    assert_eq!(worst_keys[0].0, 'b');
+
+    assert_eq!(key_stats.get_slowest_keys()[0].0, 'b');
+  }
+
+  #[test]
+  fn aggregate_bigrams() {
+    let mut key_stats = KeyStats::default();
+    key_stats.add_keystroke_log(&get_test_keystroke_log());
+
+    // The backspace breaks the `ab` transition typed correctly
+    let ab_stat = key_stats.bigrams.get(&('a', 'b')).unwrap();
+    assert_eq!(ab_stat.hits, 1);
+    assert_eq!(ab_stat.get_error_rate(), 1.0);
+
+    assert_eq!(key_stats.bigrams.get(&('b', 'a')).unwrap().hits, 1);
+  }
+
+  #[test]
+  fn weight_weak_words() {
+    let mut key_stats = KeyStats::default();
+    key_stats.add_keystroke_log(&get_test_keystroke_log());
+
+    assert_eq!(key_stats.get_word_weight("xyz"), 1.0);
+    assert!(key_stats.get_word_weight("ab") > key_stats.get_word_weight("a"));
+    assert!(key_stats.get_word_weight("ba") < key_stats.get_word_weight("ab"));
+
+    assert_eq!(key_stats.get_weakest(2), vec!["ab", "b"]);
+  }
+}
@@ -0,0 +1,460 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use super::storage_handler::StorageHandlerError;
+
+/// Kind of the recorded keystroke
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeystrokeKind {
+  // Typed char matches the expected char
+  Correct,
+
+  // Typed char does not match the expected char
+  Incorrect,
+
+  // Removed the typed char
+  Backspace,
+}
+
+impl KeystrokeKind {
+  fn to_byte(self) -> u8 {
+    match self {
+      KeystrokeKind::Correct => 0,
+      KeystrokeKind::Incorrect => 1,
+      KeystrokeKind::Backspace => 2,
+    }
+  }
+
+  fn from_byte(byte: u8) -> Option<Self> {
+    match byte {
+      0 => Some(KeystrokeKind::Correct),
+      1 => Some(KeystrokeKind::Incorrect),
+      2 => Some(KeystrokeKind::Backspace),
+      _ => None,
+    }
+  }
+}
+
+/// Single keystroke of a run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Keystroke {
+  // Time since the start of the run (ms)
+  offset_ms: u32,
+
+  // Typed char (the removed char for the backspace)
+  typed_char: char,
+
+  // Char expected at the cursor position (None past the end of the text)
+  expected_char: Option<char>,
+
+  kind: KeystrokeKind,
+}
+
+impl Keystroke {
+  pub fn get_offset_ms(&self) -> u32 {
+    self.offset_ms
+  }
+
+  pub fn get_typed_char(&self) -> char {
+    self.typed_char
+  }
+
+  pub fn get_expected_char(&self) -> Option<char> {
+    self.expected_char
+  }
+
+  pub fn get_kind(&self) -> KeystrokeKind {
+    self.kind
+  }
+}
+
+/// Flag of the packed kind byte, set if the expected char is missing
+const NO_EXPECTED_CHAR_FLAG: u8 = 0x80;
+
+/// Keystrokes recorded during a run
+///
+/// Stored packed, every keystroke takes:
+///   * delay since the previous keystroke (ms, LEB128 varint)
+///   * kind byte
+///   * typed char (UTF-8)
+///   * expected char (UTF-8, omitted if missing)
+///
+/// So a usual keystroke takes 4 bytes.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(into = "Vec<u8>", try_from = "Vec<u8>")]
+pub struct KeystrokeLog {
+  keystrokes: Vec<Keystroke>,
+}
+
+impl KeystrokeLog {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Records a keystroke
+  ///
+  /// The offset is the time elapsed since the start of the run.
+  pub fn push(
+    &mut self,
+    offset: Duration,
+    kind: KeystrokeKind,
+    typed_char: char,
+    expected_char: Option<char>,
+  ) {
+    let offset_ms = u32::try_from(offset.as_millis()).unwrap_or(u32::MAX);
+
+    // Keeps the offsets ordered, so the delays can be stored
+    let offset_ms = self
+      .keystrokes
+      .last()
+      .map_or(offset_ms, |last| offset_ms.max(last.offset_ms));
+
+    self.keystrokes.push(Keystroke {
+      offset_ms,
+      typed_char,
+      expected_char,
+      kind,
+    });
+  }
+
+  /// Updates the keystroke which typed the last grapheme left in the input
+  ///
+  /// Used when the grapheme is completed by a following char (i.e. a combining accent),
+  /// the char is merged into the keystroke instead of being recorded.
+  /// The typed char is replaced by the composed char, if there is any.
+  pub fn complete_last_typed(&mut self, kind: KeystrokeKind, composed_char: Option<char>) {
+    // Count of the later typed graphemes, which were removed
+    let mut removed_count = 0;
+
+    for keystroke in self.keystrokes.iter_mut().rev() {
+      match keystroke.kind {
+        KeystrokeKind::Backspace => removed_count += 1,
+        KeystrokeKind::Correct | KeystrokeKind::Incorrect if removed_count > 0 => {
+          removed_count -= 1;
+        }
+        KeystrokeKind::Correct | KeystrokeKind::Incorrect => {
+          keystroke.kind = kind;
+          keystroke.typed_char = composed_char.unwrap_or(keystroke.typed_char);
+          return;
+        }
+      }
+    }
+  }
+
+  pub fn get_keystrokes(&self) -> &[Keystroke] {
+    &self.keystrokes
+  }
+
+  /// Returns the keystrokes recorded until the offset (including)
+  pub fn get_keystrokes_until(&self, offset: Duration) -> &[Keystroke] {
+    let offset_ms = u32::try_from(offset.as_millis()).unwrap_or(u32::MAX);
+
+    let keystrokes_count = self
+      .keystrokes
+      .partition_point(|keystroke| keystroke.offset_ms <= offset_ms);
+
+    &self.keystrokes[..keystrokes_count]
+  }
+
+  /// Returns the text typed until the offset (including)
+  ///
+  /// Every backspace removes the last typed char.
+  pub fn get_typed_text_at(&self, offset: Duration) -> String {
+    let mut typed_chars = Vec::new();
+
+    for keystroke in self.get_keystrokes_until(offset) {
+      match keystroke.kind {
+        KeystrokeKind::Backspace => {
+          typed_chars.pop();
+        }
+        KeystrokeKind::Correct | KeystrokeKind::Incorrect => typed_chars.push(keystroke.typed_char),
+      }
+    }
+
+    typed_chars.into_iter().collect()
+  }
+
+  /// Returns the counts of the corrected and uncorrected mistakes
+  ///
+  /// A mistake is corrected, if the incorrectly typed char was removed by a backspace.
+  pub fn get_errors_counts(&self) -> (usize, usize) {
+    let mut corrected_errors = 0;
+
+    // Whether the typed chars (before the cursor) were mistaken
+    let mut typed_mistakes = Vec::new();
+
+    for keystroke in self.keystrokes.iter() {
+      match keystroke.kind {
+        KeystrokeKind::Correct => typed_mistakes.push(false),
+        KeystrokeKind::Incorrect => typed_mistakes.push(true),
+        KeystrokeKind::Backspace => {
+          if typed_mistakes.pop() == Some(true) {
+            corrected_errors += 1;
+          }
+        }
+      }
+    }
+
+    let uncorrected_errors = typed_mistakes
+      .into_iter()
+      .filter(|is_mistake| *is_mistake)
+      .count();
+
+    (corrected_errors, uncorrected_errors)
+  }
+
+  pub fn len(&self) -> usize {
+    self.keystrokes.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.keystrokes.is_empty()
+  }
+
+  /// Packs the keystrokes into bytes
+  pub fn encode(&self) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(self.keystrokes.len() * 4);
+    let mut last_offset_ms = 0;
+
+    for keystroke in self.keystrokes.iter() {
+      write_varint(&mut bytes, keystroke.offset_ms - last_offset_ms);
+      last_offset_ms = keystroke.offset_ms;
+
+      let mut kind_byte = keystroke.kind.to_byte();
+
+      if keystroke.expected_char.is_none() {
+        kind_byte |= NO_EXPECTED_CHAR_FLAG;
+      }
+
+      bytes.push(kind_byte);
+      write_char(&mut bytes, keystroke.typed_char);
+
+      if let Some(expected_char) = keystroke.expected_char {
+        write_char(&mut bytes, expected_char);
+      }
+    }
+
+    bytes
+  }
+
+  /// Unpacks the keystrokes from bytes
+  pub fn decode(bytes: &[u8]) -> Result<Self, StorageHandlerError> {
+    let mut keystrokes = Vec::new();
+    let mut bytes = bytes;
+    let mut offset_ms = 0_u32;
+
+    while !bytes.is_empty() {
+      let delay_ms = read_varint(&mut bytes)?;
+      offset_ms = offset_ms.saturating_add(delay_ms);
+
+      let (&kind_byte, rest_bytes) = bytes.split_first().ok_or_else(get_truncated_error)?;
+      bytes = rest_bytes;
+
+      let kind = KeystrokeKind::from_byte(kind_byte & !NO_EXPECTED_CHAR_FLAG)
+        .ok_or_else(|| StorageHandlerError::new(format!("Unknown keystroke kind {kind_byte}")))?;
+
+      let typed_char = read_char(&mut bytes)?;
+
+      let expected_char = if kind_byte & NO_EXPECTED_CHAR_FLAG == 0 {
+        Some(read_char(&mut bytes)?)
+      } else {
+        None
+      };
+
+      keystrokes.push(Keystroke {
+        offset_ms,
+        typed_char,
+        expected_char,
+        kind,
+      });
+    }
+
+    Ok(Self { keystrokes })
+  }
+}
+
+impl From<KeystrokeLog> for Vec<u8> {
+  fn from(keystroke_log: KeystrokeLog) -> Self {
+    keystroke_log.encode()
+  }
+}
+
+impl TryFrom<Vec<u8>> for KeystrokeLog {
+  type Error = StorageHandlerError;
+
+  fn try_from(bytes: Vec<u8>) -> Result<Self, Self::Error> {
+    KeystrokeLog::decode(&bytes)
+  }
+}
+
+fn get_truncated_error() -> StorageHandlerError {
+  StorageHandlerError::new(String::from("Truncated keystroke log"))
+}
+
+fn write_varint(bytes: &mut Vec<u8>, mut value: u32) {
+  while value >= 0x80 {
+    bytes.push((value as u8) | 0x80);
+    value >>= 7;
+  }
+
+  bytes.push(value as u8);
+}
+
+fn read_varint(bytes: &mut &[u8]) -> Result<u32, StorageHandlerError> {
+  let mut value = 0_u32;
+
+  for shift in (0..32).step_by(7) {
+    let (&byte, rest_bytes) = bytes.split_first().ok_or_else(get_truncated_error)?;
+    *bytes = rest_bytes;
+
+    value |= ((byte & 0x7f) as u32) << shift;
+
+    if byte & 0x80 == 0 {
+      return Ok(value);
+    }
+  }
+
+  Err(StorageHandlerError::new(String::from(
+    "Invalid keystroke delay",
+  )))
+}
+
+fn write_char(bytes: &mut Vec<u8>, c: char) {
+  let mut char_buf = [0; 4];
+  bytes.extend_from_slice(c.encode_utf8(&mut char_buf).as_bytes());
+}
+
+fn read_char(bytes: &mut &[u8]) -> Result<char, StorageHandlerError> {
+  let char_len = match bytes.first().ok_or_else(get_truncated_error)? {
+    byte if byte >> 7 == 0 => 1,
+    byte if byte >> 5 == 0b110 => 2,
+    byte if byte >> 4 == 0b1110 => 3,
+    _ => 4,
+  };
+
+  let char_bytes = bytes.get(..char_len).ok_or_else(get_truncated_error)?;
+
+  let c = std::str::from_utf8(char_bytes)
+    .ok()
+    .and_then(|char_str| char_str.chars().next())
+    .ok_or_else(|| StorageHandlerError::new(String::from("Invalid keystroke char")))?;
+
+  *bytes = &bytes[char_len..];
+  Ok(c)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn get_test_keystroke_log() -> KeystrokeLog {
+    let mut keystroke_log = KeystrokeLog::new();
+
+    keystroke_log.push(
+      Duration::from_millis(0),
+      KeystrokeKind::Correct,
+      'h',
+      Some('h'),
+    );
+    keystroke_log.push(
+      Duration::from_millis(180),
+      KeystrokeKind::Incorrect,
+      'ř',
+      Some('e'),
+    );
+    keystroke_log.push(
+      Duration::from_millis(95_000),
+      KeystrokeKind::Backspace,
+      'ř',
+      Some('e'),
+    );
+    keystroke_log.push(
+      Duration::from_millis(95_120),
+      KeystrokeKind::Correct,
+      '🔥',
+      None,
+    );
+
+    keystroke_log
+  }
+
+  #[test]
+  fn encode_decode() {
+    let keystroke_log = get_test_keystroke_log();
+    let bytes = keystroke_log.encode();
+
+    assert_eq!(KeystrokeLog::decode(&bytes).unwrap(), keystroke_log);
+    assert_eq!(keystroke_log.get_keystrokes()[2].get_offset_ms(), 95_000);
+  }
+
+  #[test]
+  fn encode_compactly() {
+    let mut keystroke_log = KeystrokeLog::new();
+
+    for i in 0..100 {
+      keystroke_log.push(
+        Duration::from_millis(i * 100),
+        KeystrokeKind::Correct,
+        'a',
+        Some('a'),
+      );
+    }
+
+    assert_eq!(keystroke_log.encode().len(), 100 * 4);
+  }
+
+  #[test]
+  fn find_keystrokes_until() {
+    let keystroke_log = get_test_keystroke_log();
+
+    assert_eq!(
+      keystroke_log
+        .get_keystrokes_until(Duration::from_millis(180))
+        .len(),
+      2
+    );
+    assert_eq!(
+      keystroke_log.get_typed_text_at(Duration::from_millis(95_000)),
+      "h"
+    );
+    assert_eq!(keystroke_log.get_typed_text_at(Duration::MAX), "h🔥");
+  }
+
+  #[test]
+  fn count_errors() {
+    let mut keystroke_log = get_test_keystroke_log();
+    keystroke_log.push(
+      Duration::from_millis(95_200),
+      KeystrokeKind::Incorrect,
+      'x',
+      None,
+    );
+
+    assert_eq!(keystroke_log.get_errors_counts(), (1, 1));
+  }
+
+  #[test]
+  fn complete_last_typed_keystroke() {
+    let mut keystroke_log = get_test_keystroke_log();
+    keystroke_log.push(
+      Duration::from_millis(95_200),
+      KeystrokeKind::Backspace,
+      '🔥',
+      None,
+    );
+
+    // The removed graphemes are skipped
+    keystroke_log.complete_last_typed(KeystrokeKind::Incorrect, Some('ĥ'));
+
+    let first_keystroke = keystroke_log.get_keystrokes()[0];
+    assert_eq!(first_keystroke.get_kind(), KeystrokeKind::Incorrect);
+    assert_eq!(first_keystroke.get_typed_char(), 'ĥ');
+  }
+
+  #[test]
+  fn reject_truncated_bytes() {
+    let bytes = get_test_keystroke_log().encode();
+    assert!(KeystrokeLog::decode(&bytes[..bytes.len() - 1]).is_err());
+  }
+}
@@ -0,0 +1,148 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::config::{Language, TukaiLayoutName, TypingDuration, TypingMode};
+use crate::screens::ActiveScreenEnum;
+
+use super::keystroke::KeystrokeLog;
+use super::stats::Stat;
+use super::storage_data::{DEFAULT_STORAGE_DATA, StorageData};
+
+/// Stat saved without the header (without the run metadata)
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct UnversionedStat {
+  typing_duration: TypingDuration,
+
+  average_wpm: usize,
+
+  raw_wpm: usize,
+
+  accuracy: f64,
+
+  true_accuracy: f64,
+}
+
+/// Storage data saved without the header
+///
+/// The selected language was saved as an index into the embedded dictionaries.
+type UnversionedData = (
+  Vec<UnversionedStat>,
+  TypingDuration,
+  TukaiLayoutName,
+  bool,
+  usize,
+);
+
+/// Migrations of the storage data
+///
+/// Keeps the layouts of the older versions,
+/// so they can be converted into the current `StorageData`.
+pub struct Migration;
+
+impl Migration {
+  /// Migrates the data saved without the header (version 0)
+  ///
+  /// The data were saved as a bincode `Option` of the tuple.
+  pub fn migrate_unversioned(bytes: &[u8]) -> Result<StorageData> {
+    let data = bincode::deserialize::<Option<UnversionedData>>(bytes)?
+      .map(Migration::from_unversioned)
+      .unwrap_or_else(|| DEFAULT_STORAGE_DATA.clone());
+
+    Ok(data)
+  }
+
+  /// Converts the tuple into the `StorageData`
+  ///
+  /// The metadata of the older runs are unknown, so the completion time is `None`,
+  /// the language code is empty and the runs have no keystrokes (nor metrics).
+  /// The runs were timed and lasted the whole typing duration.
+  fn from_unversioned(data: UnversionedData) -> StorageData {
+    let (stats, typing_duration, layout_name, has_transparent_bg, language_index) = data;
+
+    let stats = stats
+      .into_iter()
+      .map(|stat| Stat {
+        elapsed_ms: stat.typing_duration.as_seconds() as u64 * 1000,
+        typing_duration: stat.typing_duration,
+        typing_mode: TypingMode::Time,
+        average_wpm: stat.average_wpm,
+        raw_wpm: stat.raw_wpm,
+        accuracy: stat.accuracy,
+        true_accuracy: stat.true_accuracy,
+        completed_at: None,
+        lang_code: String::new(),
+        screen: ActiveScreenEnum::Typing,
+        text_len: 0,
+        text: String::new(),
+        typed_chars: 0,
+        keystroke_log: KeystrokeLog::new(),
+        metrics: None,
+      })
+      .collect();
+
+    StorageData {
+      stats,
+      typing_duration,
+      layout_name,
+      has_transparent_bg,
+      lang_code: Language::get_embedded_lang_code(language_index).unwrap_or_default(),
+      ..DEFAULT_STORAGE_DATA.clone()
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn migrate_unversioned_data() {
+    let stat = UnversionedStat {
+      typing_duration: TypingDuration::Minute,
+      average_wpm: 16,
+      raw_wpm: 17,
+      accuracy: 93.75,
+      true_accuracy: 92.5,
+    };
+
+    let data: UnversionedData = (
+      vec![stat],
+      TypingDuration::FifteenSec,
+      TukaiLayoutName::Anime,
+      true,
+      1,
+    );
+
+    let bytes = bincode::serialize(&Some(data)).unwrap();
+    let data = Migration::migrate_unversioned(&bytes).unwrap();
+
+    assert_eq!(data.typing_duration, TypingDuration::FifteenSec);
+    assert_eq!(data.layout_name, TukaiLayoutName::Anime);
+    assert!(data.has_transparent_bg);
+    assert_eq!(Some(data.lang_code), Language::get_embedded_lang_code(1));
+    assert!(data.has_auto_indent);
+    assert!(!data.has_punctuation);
+
+    let stat = &data.stats[0];
+    assert_eq!(stat.get_average_wpm(), 16);
+    assert_eq!(stat.get_true_accuracy(), 92.5);
+    assert_eq!(stat.get_completed_at(), None);
+    assert_eq!(stat.get_lang_code(), "");
+    assert_eq!(stat.get_typing_mode(), TypingMode::Time);
+    assert_eq!(stat.get_elapsed().as_secs(), 60);
+    assert!(stat.get_metrics().is_none());
+  }
+
+  #[test]
+  fn migrate_empty_data() {
+    let bytes = bincode::serialize(&None::<UnversionedData>).unwrap();
+    let data = Migration::migrate_unversioned(&bytes).unwrap();
+
+    assert!(data.stats.is_empty());
+  }
+
+  #[test]
+  fn reject_unknown_data() {
+    assert!(Migration::migrate_unversioned(&[7, 1, 2]).is_err());
+  }
+}
@@ -1,3 +1,11 @@
+pub mod key_stats;
+pub mod keystroke;
+pub mod migration;
+pub mod stat_export;
+pub mod stat_filter;
 pub mod stat_helper;
+pub mod stat_import;
 pub mod stats;
+pub mod storage_data;
 pub mod storage_handler;
+pub mod wpm_timeline;
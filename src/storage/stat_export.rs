@@ -0,0 +1,217 @@
+use std::{
+  fs::File,
+  io::{BufWriter, Write},
+  path::Path,
+  time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::stats::Stat;
+use crate::{config::TypingMode, screens::ActiveScreenEnum};
+
+/// Format of the exported stats
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum ExportFormat {
+  Csv,
+  Json,
+}
+
+impl ExportFormat {
+  /// Returns the file extension of the format
+  pub fn get_extension(&self) -> &'static str {
+    match self {
+      ExportFormat::Csv => "csv",
+      ExportFormat::Json => "json",
+    }
+  }
+
+  /// Returns the format matching the file extension
+  ///
+  /// i.e. `stats.json` -> `Json`
+  pub fn from_path<P: AsRef<Path>>(path: P) -> Option<Self> {
+    let extension = path.as_ref().extension()?.to_str()?.to_lowercase();
+
+    match extension.as_str() {
+      "csv" => Some(ExportFormat::Csv),
+      "json" => Some(ExportFormat::Json),
+      _ => None,
+    }
+  }
+}
+
+/// Flat representation of a `Stat`
+///
+/// Used as a row of the CSV or as an object of the JSON.
+/// The run metadata are optional, so older exports can be imported.
+/// The keystroke log is not exported.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct StatRecord {
+  pub duration_secs: usize,
+  pub average_wpm: usize,
+  pub raw_wpm: usize,
+  pub accuracy: f64,
+  pub true_accuracy: f64,
+
+  #[serde(default)]
+  pub completed_at: Option<DateTime<Utc>>,
+
+  #[serde(default)]
+  pub lang_code: String,
+
+  #[serde(default)]
+  pub screen: ActiveScreenEnum,
+
+  #[serde(default)]
+  pub text_len: usize,
+
+  #[serde(default)]
+  pub typed_chars: usize,
+
+  #[serde(default)]
+  pub typing_mode: TypingMode,
+
+  #[serde(default)]
+  pub elapsed_secs: f64,
+}
+
+impl From<&Stat> for StatRecord {
+  fn from(stat: &Stat) -> Self {
+    Self {
+      duration_secs: stat.get_typing_duration().as_seconds(),
+      average_wpm: stat.get_average_wpm(),
+      raw_wpm: stat.get_raw_wpm(),
+      accuracy: stat.get_accuracy(),
+      true_accuracy: stat.get_true_accuracy(),
+      completed_at: stat.get_completed_at(),
+      lang_code: stat.get_lang_code().to_string(),
+      screen: stat.get_screen(),
+      text_len: stat.get_text_len(),
+      typed_chars: stat.get_typed_chars(),
+      typing_mode: stat.get_typing_mode(),
+      elapsed_secs: stat.get_elapsed().as_secs_f64(),
+    }
+  }
+}
+
+/// Exports the stats history
+pub struct StatExport;
+
+impl StatExport {
+  /// Writes all stats (oldest first) in the format into the writer
+  pub fn write<W: Write>(stats: &[Stat], format: ExportFormat, writer: W) -> Result<()> {
+    let records = stats.iter().map(StatRecord::from);
+
+    match format {
+      ExportFormat::Csv => {
+        let mut csv_writer = csv::Writer::from_writer(writer);
+
+        for record in records {
+          csv_writer.serialize(record)?;
+        }
+
+        csv_writer.flush()?;
+      }
+      ExportFormat::Json => {
+        let mut writer = writer;
+        serde_json::to_writer_pretty(&mut writer, &records.collect::<Vec<StatRecord>>())?;
+        writeln!(writer)?;
+      }
+    }
+
+    Ok(())
+  }
+
+  /// Writes all stats in the format into the file
+  pub fn write_into_file<P: AsRef<Path>>(
+    stats: &[Stat],
+    format: ExportFormat,
+    path: P,
+  ) -> Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    StatExport::write(stats, format, &mut writer)?;
+    writer.flush()?;
+    Ok(())
+  }
+
+  /// Returns a timestamped file name for the export
+  ///
+  /// i.e. `tukai-stats-1700000000.csv`
+  pub fn get_file_name(format: ExportFormat) -> String {
+    let timestamp = SystemTime::now()
+      .duration_since(UNIX_EPOCH)
+      .map(|duration| duration.as_secs())
+      .unwrap_or_default();
+
+    format!("tukai-stats-{timestamp}.{}", format.get_extension())
+  }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+  use super::*;
+  use crate::config::TypingDuration;
+  use crate::storage::stat_helper::CharCounts;
+  use std::time::Duration;
+
+  /// Stats of two runs, shared by the import tests
+  pub(crate) fn get_test_stats() -> Vec<Stat> {
+    vec![
+      Stat::new(
+        TypingDuration::Minute,
+        Duration::from_secs(60),
+        CharCounts::new(240, 10, 0, 0),
+        12,
+      ),
+      Stat::new(
+        TypingDuration::FifteenSec,
+        Duration::from_secs(15),
+        CharCounts::new(60, 0, 0, 0),
+        1,
+      ),
+    ]
+  }
+
+  #[test]
+  fn export_csv() {
+    let mut bytes = Vec::new();
+    StatExport::write(&get_test_stats(), ExportFormat::Csv, &mut bytes).unwrap();
+
+    let csv = String::from_utf8(bytes).unwrap();
+    let mut lines = csv.lines();
+
+    assert_eq!(
+      lines.next(),
+      Some(
+        "duration_secs,average_wpm,raw_wpm,accuracy,true_accuracy,\
+        completed_at,lang_code,screen,text_len,typed_chars,typing_mode,elapsed_secs"
+      )
+    );
+    assert!(lines.next().unwrap().starts_with("60,"));
+    assert!(lines.next().unwrap().starts_with("15,"));
+    assert!(lines.next().is_none());
+  }
+
+  #[test]
+  fn export_json() {
+    let mut bytes = Vec::new();
+    StatExport::write(&get_test_stats(), ExportFormat::Json, &mut bytes).unwrap();
+
+    let records = serde_json::from_slice::<Vec<StatRecord>>(&bytes).unwrap();
+
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[1], StatRecord::from(&get_test_stats()[1]));
+  }
+
+  #[test]
+  fn format_from_path() {
+    assert_eq!(
+      ExportFormat::from_path("runs.JSON"),
+      Some(ExportFormat::Json)
+    );
+    assert_eq!(ExportFormat::from_path("runs.csv"), Some(ExportFormat::Csv));
+    assert_eq!(ExportFormat::from_path("runs.bin"), None);
+  }
+}
@@ -0,0 +1,418 @@
+use std::cmp::Ordering;
+
+use chrono::{DateTime, Local, TimeDelta, Utc};
+
+use crate::config::{TypingDuration, TypingMode};
+
+use super::stats::Stat;
+
+/// Range of the completion time of the runs
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum DateRange {
+  #[default]
+  All,
+  Today,
+  LastWeek,
+  LastMonth,
+}
+
+impl DateRange {
+  fn switch(self) -> Self {
+    match self {
+      DateRange::All => DateRange::Today,
+      DateRange::Today => DateRange::LastWeek,
+      DateRange::LastWeek => DateRange::LastMonth,
+      DateRange::LastMonth => DateRange::All,
+    }
+  }
+
+  fn get_label(&self) -> &'static str {
+    match self {
+      DateRange::All => "all time",
+      DateRange::Today => "today",
+      DateRange::LastWeek => "last 7 days",
+      DateRange::LastMonth => "last 30 days",
+    }
+  }
+
+  /// Verifies if the completion time is in the range
+  ///
+  /// Runs without the completion time are only in the `All` range.
+  fn contains(&self, completed_at: Option<DateTime<Utc>>, now: DateTime<Utc>) -> bool {
+    let days = match self {
+      DateRange::All => return true,
+      DateRange::Today => {
+        return completed_at.is_some_and(|completed_at| {
+          completed_at.with_timezone(&Local).date_naive() == now.with_timezone(&Local).date_naive()
+        });
+      }
+      DateRange::LastWeek => 7,
+      DateRange::LastMonth => 30,
+    };
+
+    completed_at.is_some_and(|completed_at| completed_at >= now - TimeDelta::days(days))
+  }
+}
+
+/// Column the runs are sorted by
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum StatSortColumn {
+  #[default]
+  Date,
+  Duration,
+  Wpm,
+  Accuracy,
+  RawWpm,
+  TrueAccuracy,
+}
+
+impl StatSortColumn {
+  fn switch(self) -> Self {
+    match self {
+      StatSortColumn::Date => StatSortColumn::Duration,
+      StatSortColumn::Duration => StatSortColumn::Wpm,
+      StatSortColumn::Wpm => StatSortColumn::Accuracy,
+      StatSortColumn::Accuracy => StatSortColumn::RawWpm,
+      StatSortColumn::RawWpm => StatSortColumn::TrueAccuracy,
+      StatSortColumn::TrueAccuracy => StatSortColumn::Date,
+    }
+  }
+
+  fn get_label(&self) -> &'static str {
+    match self {
+      StatSortColumn::Date => "date",
+      StatSortColumn::Duration => "duration",
+      StatSortColumn::Wpm => "WPM",
+      StatSortColumn::Accuracy => "accuracy",
+      StatSortColumn::RawWpm => "raw WPM",
+      StatSortColumn::TrueAccuracy => "true accuracy",
+    }
+  }
+
+  /// Compares the stats by the column (ascending)
+  ///
+  /// Runs without the completion time are the oldest.
+  fn compare(&self, a: &Stat, b: &Stat) -> Ordering {
+    match self {
+      StatSortColumn::Date => a.get_completed_at().cmp(&b.get_completed_at()),
+      StatSortColumn::Duration => a.get_elapsed().cmp(&b.get_elapsed()),
+      StatSortColumn::Wpm => a.get_average_wpm().cmp(&b.get_average_wpm()),
+      StatSortColumn::Accuracy => a.get_accuracy().total_cmp(&b.get_accuracy()),
+      StatSortColumn::RawWpm => a.get_raw_wpm().cmp(&b.get_raw_wpm()),
+      StatSortColumn::TrueAccuracy => a.get_true_accuracy().total_cmp(&b.get_true_accuracy()),
+    }
+  }
+}
+
+/// Filter and sort order of the runs displayed on the stats screen
+///
+/// Every filter is switched through the values present in the stats.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct StatFilter {
+  // Test of the runs (duration of the timed runs), None for all tests
+  typing_test: Option<(TypingMode, TypingDuration)>,
+
+  // Language code of the runs, None for all languages
+  lang_code: Option<String>,
+
+  // Range of the completion time of the runs
+  date_range: DateRange,
+
+  // Column the runs are sorted by
+  sort_column: StatSortColumn,
+
+  // Sorted from the lowest value (oldest first for the date)
+  is_ascending: bool,
+}
+
+impl StatFilter {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Verifies if the run passes the filters
+  pub fn is_matching(&self, stat: &Stat, now: DateTime<Utc>) -> bool {
+    let is_matching_test = self
+      .typing_test
+      .as_ref()
+      .is_none_or(|(typing_mode, typing_duration)| stat.is_same_test(typing_mode, typing_duration));
+
+    let is_matching_lang_code = self
+      .lang_code
+      .as_ref()
+      .is_none_or(|lang_code| stat.get_lang_code() == lang_code);
+
+    is_matching_test
+      && is_matching_lang_code
+      && self.date_range.contains(stat.get_completed_at(), now)
+  }
+
+  /// Returns the runs passing the filters
+  ///
+  /// Oldest first, the stats are not expected in any order (i.e. after an import).
+  pub fn get_filtered(&self, stats: &[Stat], now: DateTime<Utc>) -> Vec<Stat> {
+    let mut stats = stats
+      .iter()
+      .filter(|stat| self.is_matching(stat, now))
+      .cloned()
+      .collect::<Vec<Stat>>();
+
+    stats.sort_by(|a, b| StatSortColumn::Date.compare(a, b));
+    stats
+  }
+
+  /// Returns the runs passing the filters in the sort order
+  ///
+  /// Equal values keep the date order.
+  pub fn get_sorted(&self, stats: &[Stat], now: DateTime<Utc>) -> Vec<Stat> {
+    let mut stats = self.get_filtered(stats, now);
+
+    if !self.is_ascending {
+      stats.reverse();
+    }
+
+    stats.sort_by(|a, b| {
+      let ordering = self.sort_column.compare(a, b);
+
+      if self.is_ascending {
+        ordering
+      } else {
+        ordering.reverse()
+      }
+    });
+
+    stats
+  }
+
+  /// Switches to the next test present in the stats
+  pub fn switch_typing_test(&mut self, stats: &[Stat]) {
+    let mut typing_tests: Vec<(TypingMode, TypingDuration)> = Vec::new();
+
+    for stat in stats {
+      // Only the timed runs are distinguished by the duration
+      let typing_duration = match stat.get_typing_mode() {
+        TypingMode::Time => stat.get_typing_duration().clone(),
+        _ => TypingDuration::default(),
+      };
+
+      let typing_test = (stat.get_typing_mode(), typing_duration);
+
+      if !typing_tests.contains(&typing_test) {
+        typing_tests.push(typing_test);
+      }
+    }
+
+    typing_tests.sort_by_key(|(typing_mode, typing_duration)| {
+      (typing_mode.to_string(), typing_duration.as_seconds())
+    });
+
+    self.typing_test = get_next_value(&typing_tests, self.typing_test.as_ref());
+  }
+
+  /// Switches to the next language present in the stats
+  ///
+  /// Runs with an unknown language are not filtered separately.
+  pub fn switch_lang_code(&mut self, stats: &[Stat]) {
+    let mut lang_codes = stats
+      .iter()
+      .map(|stat| stat.get_lang_code().to_string())
+      .filter(|lang_code| !lang_code.is_empty())
+      .collect::<Vec<String>>();
+
+    lang_codes.sort();
+    lang_codes.dedup();
+
+    self.lang_code = get_next_value(&lang_codes, self.lang_code.as_ref());
+  }
+
+  pub fn switch_date_range(&mut self) {
+    self.date_range = self.date_range.switch();
+  }
+
+  pub fn switch_sort_column(&mut self) {
+    self.sort_column = self.sort_column.switch();
+  }
+
+  pub fn toggle_sort_order(&mut self) {
+    self.is_ascending = !self.is_ascending;
+  }
+
+  /// Clears the filters, keeps the sort order
+  pub fn clear(&mut self) {
+    self.typing_test = None;
+    self.lang_code = None;
+    self.date_range = DateRange::All;
+  }
+
+  /// Returns the description of the filters and the sort order
+  ///
+  /// i.e. `60s · en · last 7 days · WPM ↓`
+  pub fn get_description(&self) -> String {
+    let typing_test = match &self.typing_test {
+      Some((TypingMode::Time, typing_duration)) => format!("{}s", typing_duration.as_seconds()),
+      Some((typing_mode, _)) => typing_mode.to_string(),
+      None => String::from("all tests"),
+    };
+
+    let lang_code = self.lang_code.as_deref().unwrap_or("all languages");
+    let sort_order = if self.is_ascending { "↑" } else { "↓" };
+
+    format!(
+      "{typing_test} · {lang_code} · {} · {} {sort_order}",
+      self.date_range.get_label(),
+      self.sort_column.get_label()
+    )
+  }
+}
+
+/// Returns the value following the current one, None after the last value
+///
+/// Starts from the first value if there is no current value (or it is not present anymore).
+fn get_next_value<T: Clone + PartialEq>(values: &[T], current: Option<&T>) -> Option<T> {
+  match current.and_then(|current| values.iter().position(|value| value == current)) {
+    Some(index) => values.get(index + 1).cloned(),
+    None => values.first().cloned(),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::storage::stat_helper::CharCounts;
+  use std::time::Duration;
+
+  fn get_test_stat(
+    typing_duration: TypingDuration,
+    correct: usize,
+    lang_code: &str,
+    completed_at: DateTime<Utc>,
+  ) -> Stat {
+    let mut stat = Stat::new(
+      typing_duration.clone(),
+      typing_duration.as_duration(),
+      CharCounts::new(correct, 0, 0, 0),
+      0,
+    )
+    .lang_code(lang_code);
+
+    stat.completed_at = Some(completed_at.timestamp());
+    stat
+  }
+
+  /// Stats in the completion order
+  fn get_test_stats(now: DateTime<Utc>) -> Vec<Stat> {
+    vec![
+      get_test_stat(TypingDuration::Minute, 300, "en", now - TimeDelta::days(40)),
+      get_test_stat(
+        TypingDuration::ThirtySec,
+        200,
+        "sk",
+        now - TimeDelta::days(3),
+      ),
+      get_test_stat(TypingDuration::Minute, 250, "en", now - TimeDelta::days(1)),
+      get_test_stat(TypingDuration::Minute, 200, "sk", now),
+    ]
+  }
+
+  #[test]
+  fn filter_stats() {
+    let now = Utc::now();
+    let stats = get_test_stats(now);
+
+    let mut stat_filter = StatFilter::new();
+
+    // Newest first by default
+    let sorted_stats = stat_filter.get_sorted(&stats, now);
+    assert_eq!(sorted_stats.len(), 4);
+    assert!(sorted_stats[0].is_same_run(&stats[3]));
+
+    // 30s, then 60s
+    stat_filter.switch_typing_test(&stats);
+    stat_filter.switch_typing_test(&stats);
+    assert_eq!(stat_filter.get_filtered(&stats, now).len(), 3);
+
+    stat_filter.switch_lang_code(&stats);
+    assert_eq!(stat_filter.get_filtered(&stats, now).len(), 2);
+
+    stat_filter.switch_date_range();
+    stat_filter.switch_date_range();
+    assert_eq!(stat_filter.get_filtered(&stats, now).len(), 1);
+    assert_eq!(
+      stat_filter.get_description(),
+      "60s · en · last 7 days · date ↓"
+    );
+
+    stat_filter.clear();
+    assert_eq!(stat_filter.get_filtered(&stats, now).len(), 4);
+
+    // Switched back to all tests after the last one
+    stat_filter.switch_typing_test(&stats);
+    stat_filter.switch_typing_test(&stats);
+    stat_filter.switch_typing_test(&stats);
+    assert_eq!(stat_filter, StatFilter::new());
+  }
+
+  #[test]
+  fn sort_stats() {
+    let now = Utc::now();
+    let stats = get_test_stats(now);
+
+    let mut stat_filter = StatFilter::new();
+
+    // Date, duration, then WPM
+    stat_filter.switch_sort_column();
+    stat_filter.switch_sort_column();
+
+    let wpms = |stat_filter: &StatFilter| {
+      stat_filter
+        .get_sorted(&stats, now)
+        .iter()
+        .map(|stat| stat.get_average_wpm())
+        .collect::<Vec<usize>>()
+    };
+
+    assert_eq!(wpms(&stat_filter), vec![80, 60, 50, 40]);
+
+    stat_filter.toggle_sort_order();
+    assert_eq!(wpms(&stat_filter), vec![40, 50, 60, 80]);
+
+    // Equal durations keep the date order
+    stat_filter.switch_sort_column();
+    stat_filter.switch_sort_column();
+    stat_filter.switch_sort_column();
+    stat_filter.switch_sort_column();
+    stat_filter.switch_sort_column();
+
+    let sorted_stats = stat_filter.get_sorted(&stats, now);
+    assert_eq!(sorted_stats[0].get_elapsed(), Duration::from_secs(30));
+    assert!(sorted_stats[1].is_same_run(&stats[0]));
+    assert!(sorted_stats[3].is_same_run(&stats[3]));
+  }
+
+  #[test]
+  fn sort_imported_stats_by_date() {
+    let now = Utc::now();
+    let stats = get_test_stats(now);
+
+    // Imported runs are appended after the newer runs
+    let mut mixed_stats = vec![stats[3].clone(), stats[1].clone(), stats[0].clone()];
+
+    let mut stat_without_date = stats[2].clone();
+    stat_without_date.completed_at = None;
+    mixed_stats.push(stat_without_date.clone());
+
+    let mut stat_filter = StatFilter::new();
+
+    let sorted_stats = stat_filter.get_sorted(&mixed_stats, now);
+    assert!(sorted_stats[0].is_same_run(&stats[3]));
+    assert!(sorted_stats[2].is_same_run(&stats[0]));
+    assert!(sorted_stats[3].is_same_run(&stat_without_date));
+
+    stat_filter.toggle_sort_order();
+
+    let filtered_stats = stat_filter.get_filtered(&mixed_stats, now);
+    assert_eq!(stat_filter.get_sorted(&mixed_stats, now), filtered_stats);
+    assert!(filtered_stats[0].is_same_run(&stat_without_date));
+    assert!(filtered_stats[3].is_same_run(&stats[3]));
+  }
+}
@@ -1,25 +1,537 @@
+//! Scoring model of the runs
+//!
+//! The typed text is compared with the expected text word by word
+//! (words are separated by whitespaces, chars are graphemes),
+//! so a skipped or doubled char does not shift the rest of the text:
+//!
+//!   * correct: typed char matching the expected char (including the separators)
+//!   * incorrect: typed char not matching the expected char
+//!   * extra: typed char past the end of the expected word (or text)
+//!   * missed: expected char of a finished word which was not typed
+//!
+//! The word in progress at the end of the run has no missed chars.
+//!
+//! The typing screens align the input the same way (see `StatHelper::compare_texts`),
+//! a whitespace moves the cursor to the next word and the keystrokes are recorded
+//! by the kinds of the typed chars (extra chars are incorrect keystrokes).
+//!
+//! From the counts and the time actually spent (first keystroke to the end of the run):
+//!
+//!   * WPM: correct chars / 5 per minute
+//!   * Raw WPM: typed chars (correct, incorrect and extra) / 5 per minute
+//!   * Accuracy: correct chars / all counted chars (missed included)
+//!   * True accuracy: as the accuracy, but the mistakes corrected during the run
+//!     are counted as the mistaken chars as well
+
+use crate::graphemes::GraphemeText;
+
+/// Count of the chars in the average word
+const WORD_LEN: f64 = 5.0;
+
+/// Kind of the char compared with the expected text
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CharKind {
+  // Typed char matching the expected char
+  Correct,
+
+  // Typed char not matching the expected char
+  Incorrect,
+
+  // Typed char past the end of the expected word (or text)
+  Extra,
+
+  // Expected char of a finished word which was not typed
+  Missed,
+
+  // Expected char which was not typed yet
+  Untyped,
+}
+
+/// Grapheme of the typed text aligned with the expected text
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ComparedGrapheme<'a> {
+  // Position of the expected grapheme in the expected text (None for the extra grapheme)
+  expected_index: Option<usize>,
+
+  // Expected grapheme (None for the extra grapheme)
+  expected: Option<&'a str>,
+
+  // Typed grapheme (None for the missed and untyped graphemes)
+  typed: Option<&'a str>,
+
+  kind: CharKind,
+}
+
+impl<'a> ComparedGrapheme<'a> {
+  pub fn get_expected_index(&self) -> Option<usize> {
+    self.expected_index
+  }
+
+  pub fn get_expected(&self) -> Option<&'a str> {
+    self.expected
+  }
+
+  pub fn get_typed(&self) -> Option<&'a str> {
+    self.typed
+  }
+
+  pub fn get_kind(&self) -> CharKind {
+    self.kind
+  }
+
+  /// Returns the displayed grapheme
+  ///
+  /// The expected grapheme, the typed grapheme for the extra grapheme.
+  pub fn get_displayed(&self) -> &'a str {
+    self.expected.or(self.typed).unwrap_or_default()
+  }
+}
+
+/// Counts of the chars of the run
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CharCounts {
+  // Typed chars matching the expected chars
+  correct: usize,
+
+  // Typed chars not matching the expected chars
+  incorrect: usize,
+
+  // Typed chars past the end of the expected words
+  extra: usize,
+
+  // Expected chars skipped in the finished words
+  missed: usize,
+}
+
+impl CharCounts {
+  #[cfg(test)]
+  pub fn new(correct: usize, incorrect: usize, extra: usize, missed: usize) -> Self {
+    Self {
+      correct,
+      incorrect,
+      extra,
+      missed,
+    }
+  }
+
+  /// Compares the typed text with the expected text word by word
+  #[cfg(test)]
+  pub fn from_texts(expected_text: &str, typed_text: &str) -> Self {
+    CharCounts::from_compared(&StatHelper::compare_texts(expected_text, typed_text))
+  }
+
+  /// Counts the kinds of the compared graphemes
+  pub fn from_compared(compared: &[ComparedGrapheme]) -> Self {
+    let mut char_counts = CharCounts::default();
+
+    for compared_grapheme in compared {
+      match compared_grapheme.kind {
+        CharKind::Correct => char_counts.correct += 1,
+        CharKind::Incorrect => char_counts.incorrect += 1,
+        CharKind::Extra => char_counts.extra += 1,
+        CharKind::Missed => char_counts.missed += 1,
+        CharKind::Untyped => {}
+      }
+    }
+
+    char_counts
+  }
+
+  pub fn get_correct(&self) -> usize {
+    self.correct
+  }
+
+  pub fn get_incorrect(&self) -> usize {
+    self.incorrect
+  }
+
+  pub fn get_extra(&self) -> usize {
+    self.extra
+  }
+
+  pub fn get_missed(&self) -> usize {
+    self.missed
+  }
+
+  /// Returns the count of the typed chars (correct, incorrect and extra)
+  pub fn get_typed_count(&self) -> usize {
+    self.correct + self.incorrect + self.extra
+  }
+
+  /// Returns the count of the mistaken chars (incorrect, extra and missed)
+  pub fn get_errors_count(&self) -> usize {
+    self.incorrect + self.extra + self.missed
+  }
+}
+
+/// Splits the text into the words with their following separators (graphemes)
+///
+/// Leading whitespaces are the separator of an empty word.
+fn get_segments(text: &str) -> Vec<(Vec<&str>, Vec<&str>)> {
+  let mut segments: Vec<(Vec<&str>, Vec<&str>)> = Vec::new();
+
+  for grapheme in text.get_graphemes() {
+    let is_whitespace = grapheme.trim().is_empty();
+
+    match segments.last_mut() {
+      Some((_, separator)) if is_whitespace => separator.push(grapheme),
+      Some((word, separator)) if separator.is_empty() => word.push(grapheme),
+      _ if is_whitespace => segments.push((Vec::new(), vec![grapheme])),
+      _ => segments.push((vec![grapheme], Vec::new())),
+    }
+  }
+
+  segments
+}
+
+/// Aligns the typed part (word or separator) with the expected part
+///
+/// Typed graphemes are compared at the same positions, the rest are extra.
+/// The expected graphemes which were not typed are missed, if the part is finished.
+/// The part is not typed at all, if `typed` is None.
+fn compare_part<'a>(
+  compared: &mut Vec<ComparedGrapheme<'a>>,
+  expected_index: &mut usize,
+  expected: &[&'a str],
+  typed: Option<&[&'a str]>,
+  is_finished: bool,
+) {
+  let typed = typed.unwrap_or_default();
+
+  for (i, expected_grapheme) in expected.iter().enumerate() {
+    let typed_grapheme = typed.get(i).copied();
+
+    let kind = match typed_grapheme {
+      Some(typed_grapheme) if typed_grapheme == *expected_grapheme => CharKind::Correct,
+      Some(_) => CharKind::Incorrect,
+      None if is_finished => CharKind::Missed,
+      None => CharKind::Untyped,
+    };
+
+    compared.push(ComparedGrapheme {
+      expected_index: Some(*expected_index + i),
+      expected: Some(expected_grapheme),
+      typed: typed_grapheme,
+      kind,
+    });
+  }
+
+  for typed_grapheme in typed.iter().skip(expected.len()) {
+    compared.push(ComparedGrapheme {
+      expected_index: None,
+      expected: None,
+      typed: Some(typed_grapheme),
+      kind: CharKind::Extra,
+    });
+  }
+
+  *expected_index += expected.len();
+}
+
 pub struct StatHelper;
 
 impl StatHelper {
-  /// Calculates raw WPM
-  pub fn get_calculated_raw_wpm(chars_counter: usize, time_limit: usize) -> usize {
-    ((chars_counter as f64 / 5.0) * 60.0 / time_limit as f64) as usize
+  /// Aligns the typed text with the expected text word by word
+  ///
+  /// Returns all the expected graphemes, with the extra typed graphemes
+  /// after the words they were typed in. The typed graphemes keep their order.
+  pub fn compare_texts<'a>(
+    expected_text: &'a str,
+    typed_text: &'a str,
+  ) -> Vec<ComparedGrapheme<'a>> {
+    let expected_segments = get_segments(expected_text);
+    let typed_segments = get_segments(typed_text);
+
+    let mut compared = Vec::new();
+    let mut expected_index = 0;
+
+    for i in 0..expected_segments.len().max(typed_segments.len()) {
+      let (expected_word, expected_separator) =
+        expected_segments.get(i).cloned().unwrap_or_default();
+
+      let Some((typed_word, typed_separator)) = typed_segments.get(i) else {
+        compare_part(
+          &mut compared,
+          &mut expected_index,
+          &expected_word,
+          None,
+          false,
+        );
+        compare_part(
+          &mut compared,
+          &mut expected_index,
+          &expected_separator,
+          None,
+          false,
+        );
+        continue;
+      };
+
+      // The word is finished by the separator, otherwise it is in progress
+      let is_finished = !typed_separator.is_empty();
+
+      compare_part(
+        &mut compared,
+        &mut expected_index,
+        &expected_word,
+        Some(typed_word),
+        is_finished,
+      );
+
+      // The separator is finished by the next word
+      let is_separator_finished = i + 1 < typed_segments.len();
+
+      compare_part(
+        &mut compared,
+        &mut expected_index,
+        &expected_separator,
+        is_finished.then_some(typed_separator.as_slice()),
+        is_separator_finished,
+      );
+    }
+
+    compared
+  }
+
+  /// Calculates the WPM of the chars count (rounded)
+  ///
+  /// 0 if no time elapsed.
+  fn get_calculated_chars_wpm(chars_count: usize, elapsed_secs: f64) -> usize {
+    if elapsed_secs <= 0.0 {
+      return 0;
+    }
+
+    ((chars_count as f64 / WORD_LEN) * 60.0 / elapsed_secs).round() as usize
+  }
+
+  /// Calculates raw WPM from all typed chars
+  ///
+  /// 0 if no time elapsed.
+  pub fn get_calculated_raw_wpm(char_counts: &CharCounts, elapsed_secs: f64) -> usize {
+    StatHelper::get_calculated_chars_wpm(char_counts.get_typed_count(), elapsed_secs)
   }
 
-  /// Calculates WPM
-  pub fn get_calculated_wpm(
-    chars_counter: usize,
-    mistakes_counter: usize,
-    time_limit: usize,
-  ) -> usize {
-    (((chars_counter as f64 - mistakes_counter as f64) / 5.0) * 60.0 / time_limit as f64).round()
-      as usize
+  /// Calculates WPM from the correct chars
+  ///
+  /// 0 if no time elapsed.
+  pub fn get_calculated_wpm(char_counts: &CharCounts, elapsed_secs: f64) -> usize {
+    StatHelper::get_calculated_chars_wpm(char_counts.get_correct(), elapsed_secs)
   }
 
-  /// Calculates accuracy
+  /// Calculates accuracy (%, rounded to 2 decimals)
+  ///
+  /// 0% if there are no chars, mistakes over the chars count are capped.
   pub fn get_calculated_accuracy(chars_counter: usize, mistakes_counter: usize) -> f64 {
+    if chars_counter == 0 {
+      return 0.0;
+    }
+
     let accuracy =
-      ((chars_counter as f64 - mistakes_counter as f64) / chars_counter as f64) * 100.0;
+      (chars_counter.saturating_sub(mistakes_counter) as f64 / chars_counter as f64) * 100.0;
     (accuracy * 100.0).round() / 100.0
   }
+
+  /// Calculates accuracy of the char counts
+  ///
+  /// The missed chars are counted as mistakes.
+  pub fn get_calculated_chars_accuracy(char_counts: &CharCounts) -> f64 {
+    StatHelper::get_calculated_true_accuracy(char_counts, 0)
+  }
+
+  /// Calculates true accuracy of the char counts
+  ///
+  /// The mistakes corrected during the run are counted
+  /// as the mistaken chars in addition to the chars of the counts.
+  pub fn get_calculated_true_accuracy(char_counts: &CharCounts, corrected_errors: usize) -> f64 {
+    let errors_count = char_counts.get_errors_count() + corrected_errors;
+
+    StatHelper::get_calculated_accuracy(char_counts.get_correct() + errors_count, errors_count)
+  }
+
+  /// Calculates consistency of the speeds (i.e. WPM by seconds)
+  ///
+  /// 100% minus the coefficient of variation (standard deviation / mean) of the speeds,
+  /// at least 0%. 0% if there is no speed.
+  pub fn get_calculated_consistency(speeds: &[f64]) -> f64 {
+    if speeds.is_empty() {
+      return 0.0;
+    }
+
+    let mean = speeds.iter().sum::<f64>() / speeds.len() as f64;
+
+    if mean <= 0.0 {
+      return 0.0;
+    }
+
+    let variance = speeds
+      .iter()
+      .map(|speed| (speed - mean).powi(2))
+      .sum::<f64>()
+      / speeds.len() as f64;
+
+    let consistency = ((1.0 - variance.sqrt() / mean) * 100.0).max(0.0);
+    (consistency * 100.0).round() / 100.0
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use proptest::prelude::*;
+
+  /// Words of ASCII and accented letters separated by spaces
+  const TEXT_PATTERN: &str = "([a-zéèäöüßżółąę]{1,8} ){0,12}";
+
+  #[test]
+  fn count_chars_by_words() {
+    let char_counts = CharCounts::from_texts("hello world again", "helo wordl agai");
+
+    // helo: 3 correct, 1 incorrect, 1 missed; wordl: 3 correct, 2 incorrect;
+    // agai: in progress, so the last char is not missed
+    assert_eq!(char_counts, CharCounts::new(12, 3, 0, 1));
+
+    let char_counts = CharCounts::from_texts("ab cd", "abc cd ef");
+
+    // The separator after the last expected word is extra as well
+    assert_eq!(char_counts, CharCounts::new(5, 0, 4, 0));
+  }
+
+  #[test]
+  fn count_code_separators() {
+    let char_counts = CharCounts::from_texts("fn main() {\n    x\n}", "fn main() {\nx");
+
+    // The skipped indentation is missed
+    assert_eq!(char_counts, CharCounts::new(13, 0, 0, 4));
+  }
+
+  #[test]
+  fn compare_texts_by_words() {
+    let compared = StatHelper::compare_texts("ab cd ef", "abx c");
+
+    let kinds = compared
+      .iter()
+      .map(|compared_grapheme| compared_grapheme.get_kind())
+      .collect::<Vec<CharKind>>();
+
+    // The extra char follows its word, the word in progress is untyped
+    assert_eq!(
+      kinds,
+      [
+        CharKind::Correct,
+        CharKind::Correct,
+        CharKind::Extra,
+        CharKind::Correct,
+        CharKind::Correct,
+        CharKind::Untyped,
+        CharKind::Untyped,
+        CharKind::Untyped,
+        CharKind::Untyped,
+      ]
+    );
+
+    assert_eq!(compared[2].get_displayed(), "x");
+    assert_eq!(compared[2].get_expected_index(), None);
+    assert_eq!(compared[3].get_expected_index(), Some(2));
+  }
+
+  #[test]
+  fn calculate_scores() {
+    let char_counts = CharCounts::new(240, 6, 4, 2);
+
+    assert_eq!(StatHelper::get_calculated_wpm(&char_counts, 60.0), 48);
+    assert_eq!(StatHelper::get_calculated_raw_wpm(&char_counts, 60.0), 50);
+    assert_eq!(
+      StatHelper::get_calculated_chars_accuracy(&char_counts),
+      95.24
+    );
+    assert_eq!(
+      StatHelper::get_calculated_true_accuracy(&char_counts, 8),
+      92.31
+    );
+
+    // Both WPM are rounded the same way
+    assert_eq!(
+      StatHelper::get_calculated_raw_wpm(&CharCounts::new(0, 0, 9, 0), 60.0),
+      2
+    );
+    assert_eq!(
+      StatHelper::get_calculated_wpm(&CharCounts::new(9, 0, 0, 0), 60.0),
+      2
+    );
+  }
+
+  #[test]
+  fn score_empty_run() {
+    let char_counts = CharCounts::from_texts("hello world", "");
+
+    assert_eq!(char_counts, CharCounts::default());
+    assert_eq!(StatHelper::get_calculated_wpm(&char_counts, 0.0), 0);
+    assert_eq!(StatHelper::get_calculated_raw_wpm(&char_counts, 15.0), 0);
+    assert_eq!(StatHelper::get_calculated_chars_accuracy(&char_counts), 0.0);
+    assert_eq!(StatHelper::get_calculated_accuracy(0, 3), 0.0);
+  }
+
+  proptest! {
+    #[test]
+    fn typed_chars_are_counted_once(expected in TEXT_PATTERN, typed in TEXT_PATTERN) {
+      let char_counts = CharCounts::from_texts(&expected, &typed);
+
+      prop_assert_eq!(char_counts.get_typed_count(), typed.get_graphemes_count());
+    }
+
+    #[test]
+    fn exact_text_is_correct(text in TEXT_PATTERN, elapsed_secs in 1.0..600.0_f64) {
+      let char_counts = CharCounts::from_texts(&text, &text);
+
+      prop_assert_eq!(char_counts.get_correct(), text.get_graphemes_count());
+      prop_assert_eq!(char_counts.get_errors_count(), 0);
+      prop_assert_eq!(
+        StatHelper::get_calculated_wpm(&char_counts, elapsed_secs),
+        StatHelper::get_calculated_raw_wpm(&char_counts, elapsed_secs)
+      );
+
+      if !text.is_empty() {
+        prop_assert_eq!(StatHelper::get_calculated_chars_accuracy(&char_counts), 100.0);
+      }
+    }
+
+    #[test]
+    fn wrong_text_is_incorrect(text in "[a-zż]{1,8}( [a-zż]{1,8}){0,8}") {
+      let typed = text.replace(|c: char| c != ' ', "x");
+      let expected = text.replace(|c: char| c != ' ', "y");
+
+      let char_counts = CharCounts::from_texts(&expected, &typed);
+
+      let separators_count = text.matches(' ').count();
+
+      // Only the separators are correct
+      prop_assert_eq!(char_counts.get_correct(), separators_count);
+      prop_assert_eq!(char_counts.get_incorrect(), text.replace(' ', "").chars().count());
+      prop_assert_eq!(
+        StatHelper::get_calculated_wpm(&char_counts, 60.0),
+        (separators_count as f64 / 5.0).round() as usize
+      );
+    }
+
+    #[test]
+    fn scores_are_bounded(
+      correct in 0..10_000_usize,
+      incorrect in 0..10_000_usize,
+      extra in 0..10_000_usize,
+      missed in 0..10_000_usize,
+      elapsed_secs in -1.0..600.0_f64,
+    ) {
+      let char_counts = CharCounts::new(correct, incorrect, extra, missed);
+      let accuracy = StatHelper::get_calculated_chars_accuracy(&char_counts);
+
+      prop_assert!((0.0..=100.0).contains(&accuracy));
+      prop_assert!(
+        StatHelper::get_calculated_wpm(&char_counts, elapsed_secs)
+          <= StatHelper::get_calculated_raw_wpm(&char_counts, elapsed_secs)
+      );
+      prop_assert!(
+        (0.0..=100.0).contains(&StatHelper::get_calculated_accuracy(correct, incorrect))
+      );
+    }
+  }
 }
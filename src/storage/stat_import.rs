@@ -0,0 +1,115 @@
+use std::{
+  fs::File,
+  io::{BufReader, Read},
+  path::Path,
+};
+
+use anyhow::Result;
+
+use super::{
+  stat_export::{ExportFormat, StatRecord},
+  stats::Stat,
+  storage_data::StorageData,
+};
+
+/// Format of the imported stats
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum ImportFormat {
+  Csv,
+  Json,
+
+  // Another Tukai storage file
+  Tukai,
+}
+
+impl ImportFormat {
+  /// Returns the format matching the file extension
+  ///
+  /// Files other than `.csv` and `.json` are read as the Tukai storage file.
+  pub fn from_path<P: AsRef<Path>>(path: P) -> Self {
+    match ExportFormat::from_path(path) {
+      Some(ExportFormat::Csv) => ImportFormat::Csv,
+      Some(ExportFormat::Json) => ImportFormat::Json,
+      None => ImportFormat::Tukai,
+    }
+  }
+}
+
+/// Imports the stats history
+pub struct StatImport;
+
+impl StatImport {
+  /// Reads stats in the format from the reader
+  pub fn read<R: Read>(format: ImportFormat, mut reader: R) -> Result<Vec<Stat>> {
+    let records = match format {
+      ImportFormat::Csv => csv::Reader::from_reader(reader)
+        .deserialize::<StatRecord>()
+        .collect::<Result<Vec<StatRecord>, csv::Error>>()?,
+      ImportFormat::Json => serde_json::from_reader::<_, Vec<StatRecord>>(reader)?,
+      ImportFormat::Tukai => {
+        let mut data_bytes = Vec::new();
+        reader.read_to_end(&mut data_bytes)?;
+
+        let (data, _version) = StorageData::decode(&data_bytes)?;
+        return Ok(data.stats);
+      }
+    };
+
+    records
+      .into_iter()
+      .map(|record| Stat::try_from(record).map_err(anyhow::Error::from))
+      .collect()
+  }
+
+  /// Reads stats in the format from the file
+  pub fn read_from_file<P: AsRef<Path>>(format: ImportFormat, path: P) -> Result<Vec<Stat>> {
+    StatImport::read(format, BufReader::new(File::open(path)?))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::storage::{
+    stat_export::{StatExport, tests::get_test_stats},
+    storage_data::DEFAULT_STORAGE_DATA,
+  };
+
+  #[test]
+  fn import_exported_stats() {
+    for (export_format, import_format) in [
+      (ExportFormat::Csv, ImportFormat::Csv),
+      (ExportFormat::Json, ImportFormat::Json),
+    ] {
+      let mut bytes = Vec::new();
+      StatExport::write(&get_test_stats(), export_format, &mut bytes).unwrap();
+
+      let stats = StatImport::read(import_format, &bytes[..]).unwrap();
+      assert_eq!(stats, get_test_stats());
+    }
+  }
+
+  #[test]
+  fn import_storage_file() {
+    let mut data = DEFAULT_STORAGE_DATA.clone();
+    data.stats = get_test_stats();
+
+    let bytes = data.encode().unwrap();
+    let stats = StatImport::read(ImportFormat::Tukai, &bytes[..]).unwrap();
+
+    assert_eq!(stats, get_test_stats());
+  }
+
+  #[test]
+  fn reject_unsupported_duration() {
+    let csv = "duration_secs,average_wpm,raw_wpm,accuracy,true_accuracy\n0,50,55,98.0,97.0\n";
+    assert!(StatImport::read(ImportFormat::Csv, csv.as_bytes()).is_err());
+  }
+
+  #[test]
+  fn format_from_path() {
+    assert_eq!(ImportFormat::from_path("runs.csv"), ImportFormat::Csv);
+    assert_eq!(ImportFormat::from_path("runs.json"), ImportFormat::Json);
+    assert_eq!(ImportFormat::from_path("tukai.bin"), ImportFormat::Tukai);
+  }
+}
@@ -1,74 +1,332 @@
-use super::stat_helper::StatHelper;
-use crate::config::TypingDuration;
+use super::{
+  keystroke::{KeystrokeKind, KeystrokeLog},
+  stat_export::StatRecord,
+  stat_helper::{CharCounts, StatHelper},
+  storage_handler::StorageHandlerError,
+  wpm_timeline::WpmTimeline,
+};
+use crate::config::{TypingDuration, TypingMode};
+use crate::graphemes::GraphemeText;
+use crate::screens::ActiveScreenEnum;
+use chrono::{DateTime, Local, Utc};
 use ratatui::{
   style::{Color, Style},
   text::{Line, Span},
 };
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Metrics of the run calculated from its keystrokes
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+pub struct RunMetrics {
+  // Steadiness of the WPM by seconds (%)
+  consistency: f64,
+
+  // Highest raw WPM of a second
+  burst_wpm: usize,
+
+  // Most correct keystrokes in a row
+  longest_streak: usize,
+
+  // Count of the mistakes erased during the run
+  corrected_errors: usize,
+
+  // Count of the mistakes left in the typed text
+  uncorrected_errors: usize,
+}
+
+impl RunMetrics {
+  /// Calculates the metrics from the keystrokes of the run
+  ///
+  /// The speeds are the raw WPM of the seconds, as sampled by `WpmTimeline::get_samples`.
+  ///
+  /// None if there are no keystrokes.
+  pub fn from_keystroke_log(keystroke_log: &KeystrokeLog, elapsed: Duration) -> Option<Self> {
+    if keystroke_log.is_empty() {
+      return None;
+    }
+
+    let speeds = WpmTimeline::from(keystroke_log)
+      .get_samples(elapsed)
+      .iter()
+      .map(|sample| sample.get_raw_wpm())
+      .collect::<Vec<f64>>();
+
+    let mut metrics = RunMetrics {
+      consistency: StatHelper::get_calculated_consistency(&speeds),
+      burst_wpm: speeds.iter().fold(0.0, |a: f64, b| a.max(*b)).round() as usize,
+      ..Default::default()
+    };
+
+    let mut streak = 0;
+
+    for keystroke in keystroke_log.get_keystrokes() {
+      match keystroke.get_kind() {
+        KeystrokeKind::Correct => {
+          streak += 1;
+          metrics.longest_streak = metrics.longest_streak.max(streak);
+        }
+        KeystrokeKind::Incorrect => streak = 0,
+        KeystrokeKind::Backspace => {}
+      }
+    }
+
+    (metrics.corrected_errors, metrics.uncorrected_errors) = keystroke_log.get_errors_counts();
+
+    Some(metrics)
+  }
+
+  /// Returns the consistency (%)
+  pub fn get_consistency(&self) -> f64 {
+    self.consistency
+  }
+
+  /// Returns the highest raw WPM of a second
+  pub fn get_burst_wpm(&self) -> usize {
+    self.burst_wpm
+  }
+
+  /// Returns the most correct keystrokes in a row
+  pub fn get_longest_streak(&self) -> usize {
+    self.longest_streak
+  }
+
+  /// Returns the count of the mistakes erased during the run
+  pub fn get_corrected_errors(&self) -> usize {
+    self.corrected_errors
+  }
+
+  /// Returns the count of the mistakes left in the typed text
+  pub fn get_uncorrected_errors(&self) -> usize {
+    self.uncorrected_errors
+  }
+}
 
-#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
 pub struct Stat {
-  typing_duration: TypingDuration,
+  pub(super) typing_duration: TypingDuration,
+
+  // Type of the test
+  pub(super) typing_mode: TypingMode,
+
+  pub(super) average_wpm: usize,
+
+  pub(super) raw_wpm: usize,
+
+  pub(super) accuracy: f64,
+
+  pub(super) true_accuracy: f64,
+
+  // Completion time (unix timestamp), None for runs recorded without it
+  pub(super) completed_at: Option<i64>,
+
+  // Language code of the run (empty if unknown)
+  pub(super) lang_code: String,
+
+  // Screen where the run was typed
+  pub(super) screen: ActiveScreenEnum,
+
+  // Length of the generated text (chars)
+  pub(super) text_len: usize,
+
+  // Generated text of the run (empty for runs recorded without it)
+  pub(super) text: String,
 
-  average_wpm: usize,
+  // Count of the typed chars
+  pub(super) typed_chars: usize,
 
-  raw_wpm: usize,
+  // Actual duration of the run (ms)
+  pub(super) elapsed_ms: u64,
 
-  accuracy: f64,
+  // Keystrokes of the run (empty for runs recorded without it)
+  pub(super) keystroke_log: KeystrokeLog,
 
-  true_accuracy: f64,
+  // Metrics calculated from the keystrokes (None for runs recorded without them)
+  pub(super) metrics: Option<RunMetrics>,
+}
+
+/// Creates a Stat from the imported record
+///
+/// Fails if the record duration is not supported.
+impl TryFrom<StatRecord> for Stat {
+  type Error = StorageHandlerError;
+
+  fn try_from(record: StatRecord) -> Result<Self, Self::Error> {
+    let typing_duration = TypingDuration::from_seconds(record.duration_secs).ok_or_else(|| {
+      StorageHandlerError::new(format!(
+        "Unsupported typing duration {}s",
+        record.duration_secs
+      ))
+    })?;
+
+    // Older exports do not have the elapsed time, the runs lasted the whole duration
+    let elapsed_secs = if record.elapsed_secs > 0.0 {
+      record.elapsed_secs
+    } else {
+      typing_duration.as_seconds() as f64
+    };
+
+    Ok(Self {
+      typing_duration,
+      typing_mode: record.typing_mode,
+      average_wpm: record.average_wpm,
+      raw_wpm: record.raw_wpm,
+      accuracy: record.accuracy,
+      true_accuracy: record.true_accuracy,
+      completed_at: record
+        .completed_at
+        .map(|completed_at| completed_at.timestamp()),
+      lang_code: record.lang_code,
+      screen: record.screen,
+      text_len: record.text_len,
+      text: String::new(),
+      typed_chars: record.typed_chars,
+      elapsed_ms: (elapsed_secs * 1000.0).round() as u64,
+      keystroke_log: KeystrokeLog::new(),
+      metrics: None,
+    })
+  }
 }
 
 impl Stat {
-  /// Creates a new Stat
+  /// Creates a new Stat of the timed run completed now
   ///
-  /// Calculates the:
+  /// Calculates from the char counts and the actual elapsed time the:
   ///   * WPM
   ///   * Raw WPM
   ///   * Accuracy
+  ///   * True accuracy (including the corrected mistakes)
+  ///
+  /// See the scoring model in the `stat_helper`.
   pub fn new(
     typing_duration: TypingDuration,
-    chars_counter: usize,
-    mistakes_counter: usize,
-    true_mistakes_counter: usize,
+    elapsed: Duration,
+    char_counts: CharCounts,
+    corrected_errors: usize,
   ) -> Self {
-    let typing_duration_in_seconds = typing_duration.as_seconds();
+    let elapsed_secs = elapsed.as_secs_f64();
+    let chars_counter = char_counts.get_typed_count();
 
     Self {
-      typing_duration: typing_duration.clone(),
-      average_wpm: StatHelper::get_calculated_wpm(
-        chars_counter,
-        mistakes_counter,
-        typing_duration_in_seconds,
-      ),
-      raw_wpm: StatHelper::get_calculated_raw_wpm(chars_counter, typing_duration_in_seconds),
-      accuracy: StatHelper::get_calculated_accuracy(chars_counter, mistakes_counter),
-      true_accuracy: StatHelper::get_calculated_accuracy(chars_counter, true_mistakes_counter),
+      typing_duration,
+      typing_mode: TypingMode::Time,
+      average_wpm: StatHelper::get_calculated_wpm(&char_counts, elapsed_secs),
+      raw_wpm: StatHelper::get_calculated_raw_wpm(&char_counts, elapsed_secs),
+      accuracy: StatHelper::get_calculated_chars_accuracy(&char_counts),
+      true_accuracy: StatHelper::get_calculated_true_accuracy(&char_counts, corrected_errors),
+      completed_at: Some(Utc::now().timestamp()),
+      lang_code: String::new(),
+      screen: ActiveScreenEnum::Typing,
+      text_len: 0,
+      text: String::new(),
+      typed_chars: chars_counter,
+      elapsed_ms: u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX),
+      keystroke_log: KeystrokeLog::new(),
+      metrics: None,
     }
   }
 
+  /// Sets the type of the test
+  pub fn typing_mode(mut self, typing_mode: TypingMode) -> Self {
+    self.typing_mode = typing_mode;
+    self
+  }
+
+  /// Sets the language code of the run
+  pub fn lang_code<S: Into<String>>(mut self, lang_code: S) -> Self {
+    self.lang_code = lang_code.into();
+    self
+  }
+
+  /// Sets the screen where the run was typed
+  pub fn screen(mut self, screen: ActiveScreenEnum) -> Self {
+    self.screen = screen;
+    self
+  }
+
+  /// Sets the generated text of the run (and its length)
+  ///
+  /// The whole text is kept, so the run can be replayed
+  /// with the original graphemes.
+  pub fn text<S: Into<String>>(mut self, text: S) -> Self {
+    self.text = text.into();
+    self.text_len = self.text.get_graphemes_count();
+    self
+  }
+
+  /// Sets the keystrokes of the run
+  ///
+  /// Calculates the run metrics from the keystrokes and the elapsed time.
+  pub fn keystroke_log(mut self, keystroke_log: KeystrokeLog) -> Self {
+    self.metrics = RunMetrics::from_keystroke_log(&keystroke_log, self.get_elapsed());
+    self.keystroke_log = keystroke_log;
+    self
+  }
+
+  /// Verifies if both stats are of the same run
+  ///
+  /// Ignores the keystrokes, which are not part of the exports.
+  pub fn is_same_run(&self, other: &Stat) -> bool {
+    StatRecord::from(self) == StatRecord::from(other)
+  }
+
+  /// Verifies if the run was of the test
+  ///
+  /// The duration is compared only for the timed runs.
+  pub fn is_same_test(&self, typing_mode: &TypingMode, typing_duration: &TypingDuration) -> bool {
+    self.typing_mode == *typing_mode
+      && (self.typing_mode != TypingMode::Time || self.typing_duration == *typing_duration)
+  }
+
+  /// Returns the typing duration
+  pub fn get_typing_duration(&self) -> &TypingDuration {
+    &self.typing_duration
+  }
+
   /// Returns the average wpm
   pub fn get_average_wpm(&self) -> usize {
     self.average_wpm
   }
 
-  /// Returns the duration
+  /// Returns the difficulty of the duration
+  ///
+  /// Custom durations get the difficulty of the nearest longer standard duration,
+  /// durations over three minutes are a marathon.
   pub fn get_time_difficulty(&self) -> Span<'static> {
-    match self.typing_duration {
-      TypingDuration::FifteenSec => {
-        Span::from(" (Super short)").style(Style::default().fg(Color::Cyan))
-      }
-      TypingDuration::ThirtySec => Span::from(" (Short)").style(Style::default().fg(Color::Green)),
-      TypingDuration::Minute => Span::from(" (Medium)").style(Style::default().fg(Color::Yellow)),
-      TypingDuration::ThreeMinutes => Span::from(" (Long)").style(Style::default().fg(Color::Red)),
+    match self.typing_duration.as_seconds() {
+      0..=15 => Span::from(" (Super short)").style(Style::default().fg(Color::Cyan)),
+      16..=30 => Span::from(" (Short)").style(Style::default().fg(Color::Green)),
+      31..=60 => Span::from(" (Medium)").style(Style::default().fg(Color::Yellow)),
+      61..=180 => Span::from(" (Long)").style(Style::default().fg(Color::Red)),
+      _ => Span::from(" (Marathon)").style(Style::default().fg(Color::Magenta)),
     }
   }
 
+  /// Returns the type of the test
+  pub fn get_typing_mode(&self) -> TypingMode {
+    self.typing_mode
+  }
+
+  /// Returns the actual duration of the run
+  pub fn get_elapsed(&self) -> Duration {
+    Duration::from_millis(self.elapsed_ms)
+  }
+
   /// Returns the duration
+  ///
+  /// The word count and zen runs show the actual duration.
   pub fn get_duration_pretty(&self) -> Line<'static> {
+    if self.typing_mode == TypingMode::Time {
+      return Line::from(vec![
+        Span::from(format!("{}s", self.typing_duration.as_seconds())),
+        self.get_time_difficulty(),
+      ]);
+    }
+
     Line::from(vec![
-      Span::from(format!("{}s", self.typing_duration.as_seconds())),
-      self.get_time_difficulty(),
+      Span::from(self.typing_mode.to_string()),
+      Span::from(format!(" ({}s)", self.get_elapsed().as_secs()))
+        .style(Style::default().fg(Color::DarkGray)),
     ])
   }
 
@@ -85,4 +343,113 @@ impl Stat {
   pub fn get_true_accuracy(&self) -> f64 {
     self.true_accuracy
   }
+
+  /// Returns the completion time
+  pub fn get_completed_at(&self) -> Option<DateTime<Utc>> {
+    self
+      .completed_at
+      .and_then(|completed_at| DateTime::from_timestamp(completed_at, 0))
+  }
+
+  /// Returns the completion time in the local timezone
+  ///
+  /// i.e. `2024-05-01 18:30`, or `-` if unknown
+  pub fn get_completed_at_pretty(&self) -> String {
+    self
+      .get_completed_at()
+      .map(|completed_at| {
+        completed_at
+          .with_timezone(&Local)
+          .format("%Y-%m-%d %H:%M")
+          .to_string()
+      })
+      .unwrap_or_else(|| String::from("-"))
+  }
+
+  /// Returns the language code (empty if unknown)
+  pub fn get_lang_code(&self) -> &str {
+    &self.lang_code
+  }
+
+  /// Returns the screen where the run was typed
+  pub fn get_screen(&self) -> ActiveScreenEnum {
+    self.screen
+  }
+
+  /// Returns the length of the generated text
+  pub fn get_text_len(&self) -> usize {
+    self.text_len
+  }
+
+  /// Returns the generated text of the run (empty if unknown)
+  pub fn get_text(&self) -> &str {
+    &self.text
+  }
+
+  /// Returns the count of the typed chars
+  pub fn get_typed_chars(&self) -> usize {
+    self.typed_chars
+  }
+
+  /// Returns the keystrokes of the run
+  pub fn get_keystroke_log(&self) -> &KeystrokeLog {
+    &self.keystroke_log
+  }
+
+  /// Returns the metrics of the run (None if the keystrokes were not recorded)
+  pub fn get_metrics(&self) -> Option<&RunMetrics> {
+    self.metrics.as_ref()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn calculate_run_metrics() {
+    let mut keystroke_log = KeystrokeLog::new();
+
+    let keystrokes = [
+      (100, KeystrokeKind::Correct),
+      (200, KeystrokeKind::Correct),
+      (300, KeystrokeKind::Incorrect),
+      (400, KeystrokeKind::Backspace),
+      (500, KeystrokeKind::Correct),
+      (1100, KeystrokeKind::Correct),
+      (1200, KeystrokeKind::Correct),
+      (1300, KeystrokeKind::Correct),
+      (1400, KeystrokeKind::Incorrect),
+      (1500, KeystrokeKind::Correct),
+      (1600, KeystrokeKind::Incorrect),
+    ];
+
+    for (offset_ms, kind) in keystrokes {
+      keystroke_log.push(Duration::from_millis(offset_ms), kind, 'a', Some('a'));
+    }
+
+    let stat = Stat::new(
+      TypingDuration::Minute,
+      Duration::from_secs(2),
+      CharCounts::new(7, 2, 0, 0),
+      3,
+    )
+    .keystroke_log(keystroke_log);
+    let metrics = stat.get_metrics().unwrap();
+
+    // 4 and 6 typed chars by seconds
+    assert_eq!(metrics.get_burst_wpm(), 72);
+    assert_eq!(metrics.get_consistency(), 80.0);
+    assert_eq!(metrics.get_longest_streak(), 4);
+    assert_eq!(metrics.get_corrected_errors(), 1);
+    assert_eq!(metrics.get_uncorrected_errors(), 2);
+
+    let stat = Stat::new(
+      TypingDuration::Minute,
+      Duration::from_secs(2),
+      CharCounts::new(7, 2, 0, 0),
+      3,
+    );
+    assert!(stat.get_metrics().is_none());
+  }
 }
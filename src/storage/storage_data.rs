@@ -0,0 +1,195 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::config::{TukaiLayoutName, TypingDuration, TypingMode};
+
+use super::migration::Migration;
+use super::stats::Stat;
+use super::storage_handler::StorageHandlerError;
+
+/// Identifies the Tukai storage file
+const STORAGE_MAGIC: &[u8; 5] = b"TUKAI";
+
+/// Current version of the storage data layout
+///
+/// Increment with every released change of `StorageData` (or `Stat`),
+/// including new enum variants, and add a migration step into the `Migration`.
+pub const STORAGE_VERSION: u16 = 1;
+
+/// Size of the storage file header
+///
+/// Magic bytes, version (u16) and payload checksum (u32).
+const STORAGE_HEADER_LEN: usize = STORAGE_MAGIC.len() + 2 + 4;
+
+/// Storage data
+///
+/// Represents data saved on a device's secondary memory.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StorageData {
+  // Completed runs
+  pub stats: Vec<Stat>,
+
+  // Selected typing duration
+  pub typing_duration: TypingDuration,
+
+  // Selected type of the test
+  pub typing_mode: TypingMode,
+
+  // Selected layout name
+  pub layout_name: TukaiLayoutName,
+
+  // App background is transparent
+  pub has_transparent_bg: bool,
+
+  // Ghost cursor of the personal best is displayed
+  pub has_ghost: bool,
+
+  // Leading indentation of the code lines is skipped
+  pub has_auto_indent: bool,
+
+  // Generated text contains punctuation
+  pub has_punctuation: bool,
+
+  // Generated sentences start with a capital letter
+  pub has_capitalization: bool,
+
+  // Generated text contains numbers
+  pub has_numbers: bool,
+
+  // Selected language code (empty if no language was selected yet)
+  pub lang_code: String,
+}
+
+/// Default data for storage
+///
+/// Represents the initial or fallback data used in storage.
+pub static DEFAULT_STORAGE_DATA: StorageData = StorageData {
+  stats: Vec::new(),
+  typing_duration: TypingDuration::Minute,
+  typing_mode: TypingMode::Time,
+  layout_name: TukaiLayoutName::Iced,
+  has_transparent_bg: false,
+  has_ghost: false,
+  has_auto_indent: true,
+  has_punctuation: false,
+  has_capitalization: false,
+  has_numbers: false,
+  lang_code: String::new(),
+};
+
+impl StorageData {
+  /// Serializes the data with the storage header
+  ///
+  /// Header layout:
+  ///   * magic bytes `TUKAI`
+  ///   * version (u16, little endian)
+  ///   * checksum of the payload (u32, little endian)
+  pub fn encode(&self) -> Result<Vec<u8>> {
+    let payload = bincode::serialize(self)?;
+
+    let mut bytes = Vec::with_capacity(STORAGE_HEADER_LEN + payload.len());
+    bytes.extend_from_slice(STORAGE_MAGIC);
+    bytes.extend_from_slice(&STORAGE_VERSION.to_le_bytes());
+    bytes.extend_from_slice(&get_checksum(&payload).to_le_bytes());
+    bytes.extend_from_slice(&payload);
+
+    Ok(bytes)
+  }
+
+  /// Deserializes the data from the storage file bytes
+  ///
+  /// Data saved without the header are migrated to the current version.
+  ///
+  /// Returns the data and the version it was read from.
+  pub fn decode(bytes: &[u8]) -> Result<(StorageData, u16)> {
+    let Some(header_bytes) = bytes.strip_prefix(STORAGE_MAGIC) else {
+      return Ok((Migration::migrate_unversioned(bytes)?, 0));
+    };
+
+    if header_bytes.len() < STORAGE_HEADER_LEN - STORAGE_MAGIC.len() {
+      return Err(StorageHandlerError::new(String::from("Truncated storage header")).into());
+    }
+
+    let version = u16::from_le_bytes([header_bytes[0], header_bytes[1]]);
+    let checksum = u32::from_le_bytes([
+      header_bytes[2],
+      header_bytes[3],
+      header_bytes[4],
+      header_bytes[5],
+    ]);
+
+    let payload = &bytes[STORAGE_HEADER_LEN..];
+
+    if get_checksum(payload) != checksum {
+      return Err(StorageHandlerError::new(String::from("Storage checksum mismatch")).into());
+    }
+
+    if version != STORAGE_VERSION {
+      return Err(
+        StorageHandlerError::new(format!(
+          "Storage version {version} is not the supported version {STORAGE_VERSION}"
+        ))
+        .into(),
+      );
+    }
+
+    Ok((bincode::deserialize(payload)?, version))
+  }
+}
+
+/// Calculates the FNV-1a checksum of the bytes
+fn get_checksum(bytes: &[u8]) -> u32 {
+  bytes.iter().fold(0x811c9dc5_u32, |hash, byte| {
+    (hash ^ *byte as u32).wrapping_mul(0x01000193)
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::storage::stat_helper::CharCounts;
+  use std::time::Duration;
+
+  fn get_test_data() -> StorageData {
+    let mut data = DEFAULT_STORAGE_DATA.clone();
+    data.stats.push(Stat::new(
+      TypingDuration::Minute,
+      Duration::from_secs(60),
+      CharCounts::new(75, 5, 0, 0),
+      6,
+    ));
+    data.lang_code = String::from("es");
+    data
+  }
+
+  #[test]
+  fn encode_decode() {
+    let bytes = get_test_data().encode().unwrap();
+    let (data, version) = StorageData::decode(&bytes).unwrap();
+
+    assert_eq!(version, STORAGE_VERSION);
+    assert_eq!(data.stats.len(), 1);
+    assert_eq!(data.lang_code, "es");
+  }
+
+  #[test]
+  fn reject_corrupted_data() {
+    let mut bytes = get_test_data().encode().unwrap();
+
+    let last_index = bytes.len() - 1;
+    bytes[last_index] ^= 0xff;
+    assert!(StorageData::decode(&bytes).is_err());
+
+    bytes.truncate(STORAGE_HEADER_LEN + 4);
+    assert!(StorageData::decode(&bytes).is_err());
+  }
+
+  #[test]
+  fn reject_newer_version() {
+    let mut bytes = get_test_data().encode().unwrap();
+    bytes[STORAGE_MAGIC.len()..STORAGE_MAGIC.len() + 2]
+      .copy_from_slice(&(STORAGE_VERSION + 1).to_le_bytes());
+
+    assert!(StorageData::decode(&bytes).is_err());
+  }
+}
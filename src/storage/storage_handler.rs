@@ -1,14 +1,18 @@
 use std::{
   fmt::{Debug, Display},
   path::{Path, PathBuf},
+  time::{SystemTime, UNIX_EPOCH},
 };
 
 use anyhow::Result;
+use chrono::Utc;
 
-use crate::config::{TukaiLayoutName, TypingDuration};
+use crate::config::{TukaiLayoutName, TypingDuration, TypingMode};
 use crate::file_handler::FileHandler;
 
+use super::stat_filter::StatFilter;
 use super::stats::Stat;
+use super::storage_data::{DEFAULT_STORAGE_DATA, STORAGE_VERSION, StorageData};
 
 #[derive(Debug)]
 pub struct StorageHandlerError {
@@ -23,29 +27,12 @@ impl Display for StorageHandlerError {
 
 impl std::error::Error for StorageHandlerError {}
 
-#[allow(unused)]
 impl StorageHandlerError {
-  fn new(message: String) -> Self {
+  pub fn new(message: String) -> Self {
     Self { message }
   }
 }
 
-/// Storage data type
-///
-/// Represents types saved on a device's secondary memory.
-pub type StorageData = (Vec<Stat>, TypingDuration, TukaiLayoutName, bool, usize);
-
-/// Default data for storage
-///
-/// Represents the initial or fallback data used in storage.
-static DEFAULT_STORAGE_DATA: StorageData = (
-  Vec::<Stat>::new(),
-  TypingDuration::Minute,
-  TukaiLayoutName::Iced,
-  false,
-  0,
-);
-
 /// Represents a storage file with a specified file path
 ///
 /// Handles both read and write operations.
@@ -61,6 +48,7 @@ pub struct StorageHandler {
 /// Total statistics overview
 ///
 /// Includes the average WPM (words per minute) and average accuracy.
+#[derive(Default)]
 pub struct StatOverview {
   pub total_stats_count: usize,
   pub total_average_wpm: usize,
@@ -68,13 +56,21 @@ pub struct StatOverview {
 }
 
 impl StorageHandler {
-  /// Creates a new `storage` file
+  /// Returns the application's local directory
   ///
   /// Uses a local directory path or `/tmp` as the default location.
-  pub fn new<P: AsRef<Path>>(file_path: P) -> Self {
-    let local_dir_path = dirs::data_local_dir().unwrap_or(PathBuf::from("/tmp"));
+  pub fn get_local_dir_path() -> PathBuf {
+    dirs::data_local_dir()
+      .unwrap_or(PathBuf::from("/tmp"))
+      .join("tukai")
+  }
 
-    let full_path = local_dir_path.join("tukai").join(file_path);
+  /// Creates a new `storage` file
+  ///
+  /// Placed in the application's local directory.
+  /// An absolute `file_path` is used as is.
+  pub fn new<P: AsRef<Path>>(file_path: P) -> Self {
+    let full_path = StorageHandler::get_local_dir_path().join(file_path);
 
     Self {
       file_path: full_path,
@@ -84,40 +80,93 @@ impl StorageHandler {
 
   #[cfg(test)]
   pub fn delete_file(&self) -> Result<()> {
+    let backup_file_path = FileHandler::get_backup_file_path(&self.file_path);
+
+    if backup_file_path.exists() {
+      std::fs::remove_file(backup_file_path)?;
+    }
+
     std::fs::remove_file(&self.file_path)?;
     Ok(())
   }
 
   /// Inits empty data and write into the `storage file`
   fn init_empty_data(&mut self) -> Result<()> {
-    let data = self
+    self
       .data
       .get_or_insert_with(|| DEFAULT_STORAGE_DATA.clone());
 
-    let data_bytes = bincode::serialize(&data).unwrap();
-    FileHandler::write_bytes_into_file(&self.file_path, &data_bytes)?;
-
-    Ok(())
+    self.flush()
   }
 
   /// Inits the storage
   ///
   /// Try to read all bytes from the storage file
   /// Then set into the data
+  ///
+  /// Data of an older version are migrated and flushed.
+  /// If the file is missing or unreadable (i.e. truncated or corrupted),
+  /// the data are recovered from the backup of the last write.
+  /// An unreadable file is kept aside (refer to `backup_unreadable_file`).
   pub fn init(mut self) -> Result<Self> {
-    if !self.file_path.exists() {
+    if self.file_path.exists() {
+      let data_bytes = FileHandler::read_bytes_from_file(&self.file_path)?;
+
+      match StorageData::decode(&data_bytes) {
+        Ok((data, version)) => {
+          self.data = Some(data);
+
+          if version < STORAGE_VERSION {
+            self.flush()?;
+          }
+
+          return Ok(self);
+        }
+        Err(_) if data_bytes.is_empty() => std::fs::remove_file(&self.file_path)?,
+        Err(_) => {
+          self.backup_unreadable_file()?;
+        }
+      };
+    }
+
+    if let Some(data) = self.read_backup_data() {
+      self.data = Some(data);
+      self.flush()?;
+    } else {
       self.init_empty_data()?;
-      return Ok(self);
     }
 
-    let data_bytes = FileHandler::read_bytes_from_file(&self.file_path)?;
+    Ok(self)
+  }
+
+  /// Reads the data from the backup of the storage file
+  ///
+  /// Returns None if the backup is missing or unreadable.
+  fn read_backup_data(&self) -> Option<StorageData> {
+    let backup_file_path = FileHandler::get_backup_file_path(&self.file_path);
+    let data_bytes = FileHandler::read_bytes_from_file(backup_file_path).ok()?;
+
+    StorageData::decode(&data_bytes)
+      .ok()
+      .map(|(data, _version)| data)
+  }
 
-    match bincode::deserialize(&data_bytes) {
-      Ok(data) => self.data = data,
-      Err(_) => self.init_empty_data()?,
-    };
+  /// Moves the unreadable storage file aside
+  ///
+  /// i.e. `tukai.bin` -> `tukai.bin.1700000000.unreadable`
+  fn backup_unreadable_file(&self) -> Result<PathBuf> {
+    let timestamp = SystemTime::now()
+      .duration_since(UNIX_EPOCH)
+      .map(|duration| duration.as_secs())
+      .unwrap_or_default();
 
-    Ok(self)
+    let mut backup_file_name = self.file_path.file_name().unwrap_or_default().to_owned();
+    backup_file_name.push(format!(".{timestamp}.unreadable"));
+
+    let backup_file_path = self.file_path.with_file_name(backup_file_name);
+    std::fs::rename(&self.file_path, &backup_file_path)?;
+
+    Ok(backup_file_path)
   }
 
   /// Returns data from the storage
@@ -136,11 +185,11 @@ impl StorageHandler {
     self.data.as_mut()
   }
 
-  /// Returns the complete statistics overview
+  /// Returns the statistics overview of the filtered stats
   ///
   /// (average WPM, average accuracy)
-  pub fn get_data_for_overview(&self) -> StatOverview {
-    let stats = &self.get_data().0;
+  pub fn get_data_for_overview(&self, stat_filter: &StatFilter) -> StatOverview {
+    let stats = stat_filter.get_filtered(self.get_data_stats(), Utc::now());
 
     let (sum_wpm, sum_accuracy) = stats.iter().fold((0, 0.0), |(wpm, acc), stat| {
       (wpm + stat.get_average_wpm(), acc + stat.get_accuracy())
@@ -157,9 +206,10 @@ impl StorageHandler {
 
   /// Returns data for the chart widget
   ///
-  /// Creates a dataset for the chart and calculates the best WPM.
-  pub fn get_data_for_chart(&self) -> (usize, Vec<(f64, f64)>) {
-    let stats = &self.get_data().0;
+  /// Creates a dataset of the filtered stats (in the completion order)
+  /// and calculates the best WPM.
+  pub fn get_data_for_chart(&self, stat_filter: &StatFilter) -> (usize, Vec<(f64, f64)>) {
+    let stats = stat_filter.get_filtered(self.get_data_stats(), Utc::now());
 
     let mut best_wpm = 0_usize;
     let dataset = stats
@@ -178,48 +228,128 @@ impl StorageHandler {
     (best_wpm.max(100), dataset)
   }
 
+  /// Returns all stats
+  ///
+  /// In the storage order (usually oldest first)
+  pub fn get_data_stats(&self) -> &[Stat] {
+    &self.get_data().stats
+  }
+
   /// Returns stats in reversed order
   ///
   /// Newest first
+  #[cfg(test)]
   pub fn get_data_stats_reversed(&self) -> Vec<Stat> {
-    let stats = &self.get_data().0;
+    let stats = &self.get_data().stats;
     stats.iter().rev().cloned().collect::<Vec<Stat>>()
   }
 
-  /// Returns stats sorted by average WPM
+  /// Returns the filtered stats in the sort order of the filter
+  ///
+  /// Does not depend on the storage order.
+  pub fn get_data_stats_sorted(&self, stat_filter: &StatFilter) -> Vec<Stat> {
+    stat_filter.get_sorted(self.get_data_stats(), Utc::now())
+  }
+
+  /// Returns the filtered stats sorted by average WPM
   ///
   /// Used to determine the `best score`.
-  pub fn get_data_stats_best(&self) -> Vec<Stat> {
-    let mut data = self.get_data().0.clone();
+  pub fn get_data_stats_best(&self, stat_filter: &StatFilter) -> Vec<Stat> {
+    let mut data = stat_filter.get_filtered(self.get_data_stats(), Utc::now());
     data.sort_by_key(|b| std::cmp::Reverse(b.get_average_wpm()));
     data
   }
 
   /// Returns a TypingDuration
   pub fn get_typing_duration(&self) -> TypingDuration {
-    self.get_data().1.clone()
+    self.get_data().typing_duration.clone()
+  }
+
+  /// Returns a type of the test
+  pub fn get_typing_mode(&self) -> TypingMode {
+    self.get_data().typing_mode
   }
 
   /// Returns an active layout name
   pub fn get_layout_name(&self) -> TukaiLayoutName {
-    self.get_data().2.clone()
+    self.get_data().layout_name.clone()
   }
 
-  /// Returns a current language index
-  pub fn get_language_index(&self) -> usize {
-    self.get_data().4
+  /// Returns a current language code
+  ///
+  /// Empty if no language was selected yet.
+  pub fn get_lang_code(&self) -> String {
+    self.get_data().lang_code.clone()
   }
 
   /// Returns if has a transparend background
   pub fn get_has_transparent_bg(&self) -> bool {
-    self.get_data().3
+    self.get_data().has_transparent_bg
+  }
+
+  /// Returns if has the ghost cursor of the personal best
+  pub fn get_has_ghost(&self) -> bool {
+    self.get_data().has_ghost
+  }
+
+  /// Returns if the leading indentation of the code lines is skipped
+  pub fn get_has_auto_indent(&self) -> bool {
+    self.get_data().has_auto_indent
+  }
+
+  /// Returns if the generated text contains punctuation
+  pub fn get_has_punctuation(&self) -> bool {
+    self.get_data().has_punctuation
+  }
+
+  /// Returns if the generated sentences start with a capital letter
+  pub fn get_has_capitalization(&self) -> bool {
+    self.get_data().has_capitalization
+  }
+
+  /// Returns if the generated text contains numbers
+  pub fn get_has_numbers(&self) -> bool {
+    self.get_data().has_numbers
+  }
+
+  /// Returns the personal best run with the recorded keystrokes
+  ///
+  /// Only runs of the same test (type and duration or word count)
+  /// and language are compared.
+  pub fn get_personal_best(
+    &self,
+    typing_mode: &TypingMode,
+    typing_duration: &TypingDuration,
+    lang_code: &str,
+  ) -> Option<&Stat> {
+    self
+      .get_data()
+      .stats
+      .iter()
+      .filter(|stat| {
+        stat.is_same_test(typing_mode, typing_duration)
+          && stat.get_lang_code() == lang_code
+          && !stat.get_keystroke_log().is_empty()
+      })
+      .max_by_key(|stat| stat.get_average_wpm())
   }
 
   /// Serialize `StorageData` into a bytes.
   ///
   /// Flushes all serialized data to the storage file.
+  /// The previous content is kept as a backup (refer to `get_backup_file_path`),
+  /// only if it is readable, so a damaged file never replaces the last good backup.
   pub fn flush(&self) -> Result<()> {
-    let data_bytes = bincode::serialize(&self.data)?;
+    if let Ok(previous_bytes) = FileHandler::read_bytes_from_file(&self.file_path)
+      && StorageData::decode(&previous_bytes).is_ok()
+    {
+      FileHandler::write_bytes_into_file(
+        FileHandler::get_backup_file_path(&self.file_path),
+        &previous_bytes,
+      )?;
+    }
+
+    let data_bytes = self.get_data().encode()?;
     FileHandler::write_bytes_into_file(&self.file_path, &data_bytes)
   }
 
@@ -228,37 +358,118 @@ impl StorageHandler {
   /// Then try to flush this record
   pub fn insert_into_stats(&mut self, stat: &Stat) -> bool {
     if let Some(storage_data) = self.get_data_mut() {
-      storage_data.0.push(stat.clone());
+      storage_data.stats.push(stat.clone());
     }
 
     self.flush().is_ok()
   }
 
+  /// Merges the stats into the storage, skipping the already stored ones.
+  ///
+  /// Every stored run skips a single merged run, so the equal runs are counted, not collapsed
+  /// (i.e. the legacy runs without the completion time with the same results).
+  ///
+  /// The stats are kept in the completion order (runs without the completion time first).
+  /// Then flushes the data.
+  /// Returns the count of inserted stats.
+  pub fn merge_stats(&mut self, stats: Vec<Stat>) -> Result<usize> {
+    let mut inserted_count = 0;
+
+    if let Some(storage_data) = self.get_data_mut() {
+      let stored_count = storage_data.stats.len();
+
+      // Stored runs already matched by a merged run
+      let mut is_matched = vec![false; stored_count];
+
+      for stat in stats {
+        let matching_index = (0..stored_count)
+          .find(|&index| !is_matched[index] && storage_data.stats[index].is_same_run(&stat));
+
+        match matching_index {
+          Some(index) => is_matched[index] = true,
+          None => {
+            storage_data.stats.push(stat);
+            inserted_count += 1;
+          }
+        }
+      }
+
+      storage_data
+        .stats
+        .sort_by_key(|stat| stat.get_completed_at());
+    }
+
+    self.flush()?;
+    Ok(inserted_count)
+  }
+
   /// Sets a new typing duration
   pub fn set_typing_duration(&mut self, typin_duration: TypingDuration) {
     if let Some(storage_data) = self.get_data_mut() {
-      storage_data.1 = typin_duration;
+      storage_data.typing_duration = typin_duration;
+    }
+  }
+
+  /// Sets a new type of the test
+  pub fn set_typing_mode(&mut self, typing_mode: TypingMode) {
+    if let Some(storage_data) = self.get_data_mut() {
+      storage_data.typing_mode = typing_mode;
     }
   }
 
   /// Sets a new active layout name
   pub fn set_layout(&mut self, layout_name_changed: TukaiLayoutName) {
     if let Some(storage_data) = self.get_data_mut() {
-      storage_data.2 = layout_name_changed;
+      storage_data.layout_name = layout_name_changed;
+    }
+  }
+
+  /// Sets a new language code
+  pub fn set_lang_code(&mut self, lang_code: String) {
+    if let Some(storage_data) = self.get_data_mut() {
+      storage_data.lang_code = lang_code;
+    }
+  }
+
+  /// Toggles the ghost cursor of the personal best
+  pub fn set_ghost(&mut self, state: bool) {
+    if let Some(storage_data) = self.get_data_mut() {
+      storage_data.has_ghost = state;
+    }
+  }
+
+  /// Toggles skipping of the leading indentation in the code mode
+  pub fn set_auto_indent(&mut self, state: bool) {
+    if let Some(storage_data) = self.get_data_mut() {
+      storage_data.has_auto_indent = state;
+    }
+  }
+
+  /// Toggles punctuation of the generated text
+  pub fn set_punctuation(&mut self, state: bool) {
+    if let Some(storage_data) = self.get_data_mut() {
+      storage_data.has_punctuation = state;
     }
   }
 
-  /// Sets a new language_index
-  pub fn set_language_index(&mut self, language_index: usize) {
+  /// Toggles capitalization of the generated sentences
+  pub fn set_capitalization(&mut self, state: bool) {
     if let Some(storage_data) = self.get_data_mut() {
-      storage_data.4 = language_index;
+      storage_data.has_capitalization = state;
+    }
+  }
+
+  /// Toggles numbers in the generated text
+  pub fn set_numbers(&mut self, state: bool) {
+    if let Some(storage_data) = self.get_data_mut() {
+      storage_data.has_numbers = state;
     }
   }
 
   /// Toggles a background transparency
   pub fn set_transparent_bg(&mut self, state: bool) {
     if let Some(storage_data) = self.get_data_mut() {
-      storage_data.3 = state;
+      storage_data.has_transparent_bg = state;
     }
   }
 }
@@ -266,19 +477,24 @@ impl StorageHandler {
 #[cfg(test)]
 mod tests {
   use super::*;
-  use crate::config::TypingDuration;
+  use crate::storage::keystroke::{KeystrokeKind, KeystrokeLog};
+  use crate::storage::stat_helper::CharCounts;
+  use std::time::Duration;
   use uuid::Uuid;
 
   fn get_storage_handler() -> StorageHandler {
-    let storage_helper = StorageHandler::new(format!("tests/{}.tukai", Uuid::new_v4()))
+    StorageHandler::new(format!("tests/{}.tukai", Uuid::new_v4()))
       .init()
-      .expect("Failed to initialize storage file");
-
-    storage_helper
+      .expect("Failed to initialize storage file")
   }
 
   fn get_test_stat() -> Stat {
-    Stat::new(TypingDuration::Minute, 80, 5, 6)
+    Stat::new(
+      TypingDuration::Minute,
+      Duration::from_secs(60),
+      CharCounts::new(75, 5, 0, 0),
+      6,
+    )
   }
 
   //#[test]
@@ -357,4 +573,292 @@ mod tests {
       .delete_file()
       .expect("Error occured while deleting file");
   }
+
+  #[test]
+  fn persist_lang_code() {
+    let mut storage_handler = get_storage_handler();
+    storage_handler.set_lang_code(String::from("fr"));
+    storage_handler
+      .flush()
+      .expect("Failed to flush storage data");
+
+    let storage_handler = StorageHandler::new(&storage_handler.file_path)
+      .init()
+      .expect("Failed to initialize storage file");
+
+    assert_eq!(storage_handler.get_lang_code(), "fr");
+
+    storage_handler
+      .delete_file()
+      .expect("Error occured while deleting file");
+  }
+
+  #[test]
+  fn migrate_unversioned_file() {
+    let storage_handler = get_storage_handler();
+
+    // Stat without the run metadata
+    let unversioned_stat = (TypingDuration::Minute, 16_usize, 17_usize, 93.75, 92.5);
+
+    let unversioned_data = (
+      vec![unversioned_stat],
+      TypingDuration::ThirtySec,
+      TukaiLayoutName::Rust,
+      true,
+      2_usize,
+    );
+
+    std::fs::write(
+      &storage_handler.file_path,
+      bincode::serialize(&Some(unversioned_data)).unwrap(),
+    )
+    .expect("Failed to write unversioned storage file");
+
+    let storage_handler = StorageHandler::new(&storage_handler.file_path)
+      .init()
+      .expect("Failed to initialize storage file");
+
+    assert_eq!(
+      storage_handler.get_typing_duration(),
+      TypingDuration::ThirtySec
+    );
+    assert_eq!(storage_handler.get_data_stats_reversed().len(), 1);
+
+    // Migrated data were flushed with the header
+    let data_bytes = FileHandler::read_bytes_from_file(&storage_handler.file_path).unwrap();
+    let (_, version) = StorageData::decode(&data_bytes).unwrap();
+    assert_eq!(version, STORAGE_VERSION);
+
+    storage_handler
+      .delete_file()
+      .expect("Error occured while deleting file");
+  }
+
+  #[test]
+  fn backup_unreadable_file() {
+    let storage_handler = get_storage_handler();
+
+    std::fs::write(&storage_handler.file_path, b"TUKAI broken")
+      .expect("Failed to write unreadable storage file");
+
+    let storage_handler = StorageHandler::new(&storage_handler.file_path)
+      .init()
+      .expect("Failed to initialize storage file");
+
+    assert!(storage_handler.get_data_stats_reversed().is_empty());
+
+    let file_name = storage_handler
+      .file_path
+      .file_name()
+      .unwrap()
+      .to_string_lossy()
+      .to_string();
+
+    let backup_file_path = std::fs::read_dir(storage_handler.file_path.parent().unwrap())
+      .unwrap()
+      .filter_map(|entry| entry.ok())
+      .map(|entry| entry.path())
+      .find(|path| {
+        let backup_file_name = path.file_name().unwrap().to_string_lossy();
+        backup_file_name.starts_with(&file_name) && backup_file_name.ends_with(".unreadable")
+      })
+      .expect("Unreadable storage file was not kept");
+
+    assert_eq!(std::fs::read(&backup_file_path).unwrap(), b"TUKAI broken");
+
+    std::fs::remove_file(backup_file_path).expect("Error occured while deleting backup file");
+    storage_handler
+      .delete_file()
+      .expect("Error occured while deleting file");
+  }
+
+  #[test]
+  fn recover_from_backup() {
+    let mut storage_handler = get_storage_handler();
+    storage_handler.insert_into_stats(&get_test_stat());
+    storage_handler.insert_into_stats(&get_test_stat());
+
+    // Simulates a truncated storage file
+    let data_bytes = FileHandler::read_bytes_from_file(&storage_handler.file_path).unwrap();
+    std::fs::write(
+      &storage_handler.file_path,
+      &data_bytes[..data_bytes.len() / 2],
+    )
+    .unwrap();
+
+    let storage_handler = StorageHandler::new(&storage_handler.file_path)
+      .init()
+      .expect("Failed to initialize storage file");
+
+    // The backup contains the data before the last insert
+    assert_eq!(storage_handler.get_data_stats_reversed().len(), 1);
+
+    for entry in std::fs::read_dir(storage_handler.file_path.parent().unwrap()).unwrap() {
+      let path = entry.unwrap().path();
+      let file_name = storage_handler
+        .file_path
+        .file_name()
+        .unwrap()
+        .to_string_lossy();
+
+      if path.to_string_lossy().ends_with(".unreadable")
+        && path
+          .file_name()
+          .unwrap()
+          .to_string_lossy()
+          .starts_with(&*file_name)
+      {
+        std::fs::remove_file(path).unwrap();
+      }
+    }
+
+    storage_handler
+      .delete_file()
+      .expect("Error occured while deleting file");
+  }
+
+  #[test]
+  fn keep_readable_file_as_backup() {
+    let mut storage_handler = get_storage_handler();
+    storage_handler.insert_into_stats(&get_test_stat());
+
+    let backup_file_path = FileHandler::get_backup_file_path(&storage_handler.file_path);
+    let get_backup_stats_count = || {
+      let backup_bytes = FileHandler::read_bytes_from_file(&backup_file_path).unwrap();
+      StorageData::decode(&backup_bytes).unwrap().0.stats.len()
+    };
+
+    // The backup contains the data before the last insert
+    assert_eq!(get_backup_stats_count(), 0);
+
+    // A damaged file does not replace the backup
+    std::fs::write(&storage_handler.file_path, b"TUKAI broken").unwrap();
+    storage_handler.insert_into_stats(&get_test_stat());
+
+    assert_eq!(get_backup_stats_count(), 0);
+    assert_eq!(
+      StorageData::decode(&FileHandler::read_bytes_from_file(&storage_handler.file_path).unwrap())
+        .unwrap()
+        .0
+        .stats
+        .len(),
+      2
+    );
+
+    storage_handler
+      .delete_file()
+      .expect("Error occured while deleting file");
+  }
+
+  #[test]
+  fn merge_stats_without_duplicates() {
+    let mut storage_handler = get_storage_handler();
+    storage_handler.insert_into_stats(&get_test_stat());
+
+    let mut legacy_stat = Stat::new(
+      TypingDuration::ThirtySec,
+      Duration::from_secs(30),
+      CharCounts::new(117, 3, 0, 0),
+      4,
+    );
+    legacy_stat.completed_at = None;
+
+    // Two legacy runs with the same results
+    let inserted_count = storage_handler
+      .merge_stats(vec![
+        get_test_stat(),
+        legacy_stat.clone(),
+        legacy_stat.clone(),
+      ])
+      .expect("Failed to merge stats");
+
+    assert_eq!(inserted_count, 2);
+    assert_eq!(storage_handler.get_data_stats().len(), 3);
+
+    // Merged again, only the third one is inserted
+    let inserted_count = storage_handler
+      .merge_stats(vec![legacy_stat.clone(), legacy_stat.clone(), legacy_stat])
+      .expect("Failed to merge stats");
+
+    assert_eq!(inserted_count, 1);
+    assert_eq!(storage_handler.get_data_stats().len(), 4);
+
+    storage_handler
+      .delete_file()
+      .expect("Error occured while deleting file");
+  }
+
+  #[test]
+  fn merge_stats_in_completion_order() {
+    let mut storage_handler = get_storage_handler();
+    storage_handler.insert_into_stats(&get_test_stat());
+
+    let mut older_stat = Stat::new(
+      TypingDuration::ThirtySec,
+      Duration::from_secs(30),
+      CharCounts::new(117, 3, 0, 0),
+      4,
+    );
+    older_stat.completed_at = Some(0);
+
+    let mut unknown_stat = older_stat.clone();
+    unknown_stat.completed_at = None;
+
+    storage_handler
+      .merge_stats(vec![older_stat.clone(), unknown_stat.clone()])
+      .expect("Failed to merge stats");
+
+    let stats = storage_handler.get_data_stats();
+    assert!(stats[0].is_same_run(&unknown_stat));
+    assert!(stats[1].is_same_run(&older_stat));
+    assert_eq!(stats[2].get_typing_duration(), &TypingDuration::Minute);
+
+    storage_handler
+      .delete_file()
+      .expect("Error occured while deleting file");
+  }
+
+  #[test]
+  fn personal_best_with_keystrokes() {
+    let mut storage_handler = get_storage_handler();
+
+    let mut keystroke_log = KeystrokeLog::new();
+    keystroke_log.push(Duration::ZERO, KeystrokeKind::Correct, 'a', Some('a'));
+
+    let recorded_stat = Stat::new(
+      TypingDuration::Minute,
+      Duration::from_secs(60),
+      CharCounts::new(198, 2, 0, 0),
+      3,
+    )
+    .lang_code("en")
+    .keystroke_log(keystroke_log);
+
+    // Faster runs without the keystrokes or in another language are skipped
+    storage_handler.insert_into_stats(
+      &Stat::new(
+        TypingDuration::Minute,
+        Duration::from_secs(60),
+        CharCounts::new(400, 0, 0, 0),
+        0,
+      )
+      .lang_code("en"),
+    );
+    storage_handler.insert_into_stats(&recorded_stat.clone().lang_code("fr"));
+    storage_handler.insert_into_stats(&recorded_stat);
+
+    assert_eq!(
+      storage_handler.get_personal_best(&TypingMode::Time, &TypingDuration::Minute, "en"),
+      Some(&recorded_stat)
+    );
+    assert!(
+      storage_handler
+        .get_personal_best(&TypingMode::Time, &TypingDuration::ThirtySec, "en")
+        .is_none()
+    );
+
+    storage_handler
+      .delete_file()
+      .expect("Error occured while deleting file");
+  }
 }
@@ -0,0 +1,258 @@
+use std::time::Duration;
+
+use super::keystroke::{KeystrokeKind, KeystrokeLog};
+
+/// Shortest last (partial) second of the run sampled on its own
+const MIN_PARTIAL_SECOND: Duration = Duration::from_millis(500);
+
+/// Typed chars counted in a single second of the run
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct TimelineSecond {
+  // Count of the typed chars (without backspaces)
+  typed_count: usize,
+
+  // Count of the incorrectly typed chars
+  errors_count: usize,
+
+  // Count of the correctly typed chars removed by backspaces
+  removed_correct_count: usize,
+}
+
+/// WPM sampled at the end of a second of the run
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WpmSample {
+  // Second of the run (the first second is 1)
+  second: usize,
+
+  // WPM of the chars typed within the second
+  raw_wpm: f64,
+
+  // WPM of the correct chars typed since the start of the run (without the removed chars)
+  wpm: f64,
+
+  // Count of the incorrectly typed chars within the second
+  errors_count: usize,
+}
+
+impl WpmSample {
+  pub fn get_second(&self) -> usize {
+    self.second
+  }
+
+  pub fn get_raw_wpm(&self) -> f64 {
+    self.raw_wpm
+  }
+
+  pub fn get_wpm(&self) -> f64 {
+    self.wpm
+  }
+
+  pub fn get_errors_count(&self) -> usize {
+    self.errors_count
+  }
+}
+
+/// Timeline of the run collected by seconds
+///
+/// Keystrokes are counted into the second they were typed in,
+/// the samples are calculated from the counts.
+///
+/// A correct char removed by a backspace is subtracted in the second of the backspace,
+/// so the WPM at the end of the run matches the WPM of the run.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct WpmTimeline {
+  seconds: Vec<TimelineSecond>,
+
+  // Whether the typed chars (before the cursor) were correct
+  typed_correct: Vec<bool>,
+}
+
+impl WpmTimeline {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  /// Counts the keystroke typed at the offset (time elapsed since the start of the run)
+  pub fn record(&mut self, offset: Duration, kind: KeystrokeKind) {
+    let second_index = offset.as_secs() as usize;
+
+    if self.seconds.len() <= second_index {
+      self
+        .seconds
+        .resize(second_index + 1, TimelineSecond::default());
+    }
+
+    let timeline_second = &mut self.seconds[second_index];
+
+    match kind {
+      KeystrokeKind::Correct => {
+        timeline_second.typed_count += 1;
+        self.typed_correct.push(true);
+      }
+      KeystrokeKind::Incorrect => {
+        timeline_second.typed_count += 1;
+        timeline_second.errors_count += 1;
+        self.typed_correct.push(false);
+      }
+      KeystrokeKind::Backspace => {
+        if self.typed_correct.pop() == Some(true) {
+          timeline_second.removed_correct_count += 1;
+        }
+      }
+    }
+  }
+
+  /// Returns the sample of every second of the run
+  ///
+  /// The last (partial) second is calculated from its actual length.
+  /// A partial second shorter than `MIN_PARTIAL_SECOND` is merged into the previous second,
+  /// so a keystroke just after the full second does not make a spike.
+  /// Keystrokes after the elapsed time are ignored.
+  pub fn get_samples(&self, elapsed: Duration) -> Vec<WpmSample> {
+    let elapsed_secs = elapsed.as_secs_f64();
+    let timeline_seconds_count = elapsed.as_millis().div_ceil(1000) as usize;
+
+    let partial_second = elapsed - Duration::from_secs(elapsed.as_secs());
+    let mut seconds_count = elapsed.as_secs() as usize;
+
+    if !partial_second.is_zero() && (seconds_count == 0 || partial_second >= MIN_PARTIAL_SECOND) {
+      seconds_count += 1;
+    }
+
+    let get_wpm = |chars_count: usize, secs: f64| (chars_count as f64 / 5.0) * 60.0 / secs;
+
+    let mut correct_count = 0;
+
+    (0..seconds_count)
+      .map(|second_index| {
+        let is_last = second_index + 1 == seconds_count;
+
+        // The last sample takes the rest of the run
+        let merged_seconds_count = if is_last {
+          timeline_seconds_count - second_index
+        } else {
+          1
+        };
+
+        let timeline_second = self
+          .seconds
+          .iter()
+          .skip(second_index)
+          .take(merged_seconds_count)
+          .fold(TimelineSecond::default(), |sum, timeline_second| {
+            TimelineSecond {
+              typed_count: sum.typed_count + timeline_second.typed_count,
+              errors_count: sum.errors_count + timeline_second.errors_count,
+              removed_correct_count: sum.removed_correct_count
+                + timeline_second.removed_correct_count,
+            }
+          });
+
+        correct_count += timeline_second.typed_count - timeline_second.errors_count;
+        correct_count -= timeline_second.removed_correct_count;
+
+        let second_end = if is_last {
+          elapsed_secs
+        } else {
+          (second_index + 1) as f64
+        };
+        let second_len = second_end - second_index as f64;
+
+        WpmSample {
+          second: second_index + 1,
+          raw_wpm: get_wpm(timeline_second.typed_count, second_len),
+          wpm: get_wpm(correct_count, second_end),
+          errors_count: timeline_second.errors_count,
+        }
+      })
+      .collect()
+  }
+}
+
+/// Collects the timeline of the recorded run
+impl From<&KeystrokeLog> for WpmTimeline {
+  fn from(keystroke_log: &KeystrokeLog) -> Self {
+    let mut wpm_timeline = WpmTimeline::new();
+
+    for keystroke in keystroke_log.get_keystrokes() {
+      wpm_timeline.record(
+        Duration::from_millis(keystroke.get_offset_ms() as u64),
+        keystroke.get_kind(),
+      );
+    }
+
+    wpm_timeline
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn sample_timeline() {
+    let mut wpm_timeline = WpmTimeline::new();
+
+    for offset_ms in [100, 200, 300, 400, 500] {
+      wpm_timeline.record(Duration::from_millis(offset_ms), KeystrokeKind::Correct);
+    }
+
+    wpm_timeline.record(Duration::from_millis(2100), KeystrokeKind::Incorrect);
+    wpm_timeline.record(Duration::from_millis(2200), KeystrokeKind::Backspace);
+    wpm_timeline.record(Duration::from_millis(2300), KeystrokeKind::Correct);
+
+    let samples = wpm_timeline.get_samples(Duration::from_millis(2500));
+
+    assert_eq!(samples.len(), 3);
+
+    assert_eq!(samples[0].get_second(), 1);
+    assert_eq!(samples[0].get_raw_wpm(), 60.0);
+    assert_eq!(samples[0].get_wpm(), 60.0);
+
+    // No keystrokes in the second second
+    assert_eq!(samples[1].get_raw_wpm(), 0.0);
+    assert_eq!(samples[1].get_wpm(), 30.0);
+
+    // The last second lasted a half
+    assert_eq!(samples[2].get_raw_wpm(), 48.0);
+    assert_eq!(samples[2].get_wpm(), 6.0 / 5.0 * 60.0 / 2.5);
+    assert_eq!(samples[2].get_errors_count(), 1);
+  }
+
+  #[test]
+  fn merge_short_last_second() {
+    let mut wpm_timeline = WpmTimeline::new();
+
+    for offset_ms in (0..3000).step_by(200) {
+      wpm_timeline.record(Duration::from_millis(offset_ms), KeystrokeKind::Correct);
+    }
+
+    wpm_timeline.record(Duration::from_millis(3001), KeystrokeKind::Correct);
+
+    let samples = wpm_timeline.get_samples(Duration::from_millis(3001));
+
+    // The keystroke is counted into the third second
+    assert_eq!(samples.len(), 3);
+    assert_eq!(samples[1].get_raw_wpm(), 60.0);
+    assert!(samples[2].get_raw_wpm() < 80.0);
+    assert_eq!(samples[2].get_wpm(), 16.0 / 5.0 * 60.0 / 3.001);
+  }
+
+  #[test]
+  fn subtract_removed_correct_chars() {
+    let mut wpm_timeline = WpmTimeline::new();
+
+    for offset_ms in [100, 200, 300, 400, 500] {
+      wpm_timeline.record(Duration::from_millis(offset_ms), KeystrokeKind::Correct);
+    }
+
+    wpm_timeline.record(Duration::from_millis(1100), KeystrokeKind::Backspace);
+    wpm_timeline.record(Duration::from_millis(1200), KeystrokeKind::Backspace);
+
+    let samples = wpm_timeline.get_samples(Duration::from_secs(2));
+
+    assert_eq!(samples[0].get_wpm(), 60.0);
+    assert_eq!(samples[1].get_raw_wpm(), 0.0);
+    assert_eq!(samples[1].get_wpm(), 3.0 / 5.0 * 60.0 / 2.0);
+  }
+}